@@ -0,0 +1,344 @@
+use crate::compiler_version::CompilerVersion;
+use crate::der::{
+    Element, CONTEXT_0, CONTEXT_1, GENERALIZED_TIME, INTEGER, OCTET_STRING, SEQUENCE, SET,
+};
+use crate::info::Info;
+use crate::likely_incorrect::LikelyIncorrect;
+use crate::read::{read_u16, read_u32};
+use crate::{print_heuristic, print_self_reported, Args};
+use chrono::{DateTime, Datelike, Utc};
+use exe::{Buffer, ImageDirectoryEntry, PtrPE, PE};
+
+const WIN_CERT_TYPE_PKCS_SIGNED_DATA: u16 = 0x0002;
+
+const SIGNED_DATA: &str = "1.2.840.113549.1.7.2";
+const SPC_INDIRECT_DATA: &str = "1.3.6.1.4.1.311.2.1.4";
+const SIGNING_TIME: &str = "1.2.840.113549.1.9.5";
+const COUNTERSIGNATURE: &str = "1.2.840.113549.1.9.6";
+const RFC3161_COUNTERSIGNATURE: &str = "1.3.6.1.4.1.311.3.3.1";
+const NESTED_SIGNATURE: &str = "1.3.6.1.4.1.311.2.4.1";
+
+const DIGEST_ALGORITHMS: &[(&str, &str)] = &[
+    ("1.2.840.113549.2.5", "MD5"),
+    ("1.3.14.3.2.26", "SHA-1"),
+    ("2.16.840.1.101.3.4.2.1", "SHA-256"),
+    ("2.16.840.1.101.3.4.2.2", "SHA-384"),
+    ("2.16.840.1.101.3.4.2.3", "SHA-512"),
+];
+
+const NAME_ATTRIBUTES: &[(&str, &str)] = &[
+    ("2.5.4.3", "CN"),
+    ("2.5.4.11", "OU"),
+    ("2.5.4.10", "O"),
+    ("2.5.4.7", "L"),
+    ("2.5.4.8", "S"),
+    ("2.5.4.6", "C"),
+];
+
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Signature {
+    pub subject: Option<String>,
+    pub issuer: String,
+    pub serial: String,
+    pub digest_algorithm: String,
+    pub signing_time: Option<DateTime<Utc>>,
+    pub timestamp_kind: Option<&'static str>,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub valid_to: Option<DateTime<Utc>>,
+    /// Algorithm and digest of the image from the `SpcIndirectDataContent` that was signed.
+    pub image_digest: Option<(String, Vec<u8>)>,
+}
+
+/// The `SignedData` inside a PKCS#7 `ContentInfo`.
+fn signed_data<'a>(content_info: &Element<'a>) -> Option<Element<'a>> {
+    if content_info.child(0)?.oid()? != SIGNED_DATA {
+        return None;
+    }
+
+    let explicit = content_info.child(1).filter(|a| a.tag == CONTEXT_0)?;
+    explicit.child(0).filter(|a| a.tag == SEQUENCE)
+}
+
+/// Renders the well known attributes of a distinguished name, most specific first like Windows does.
+fn name(name: &Element) -> String {
+    let mut parts = Vec::new();
+    for set in name.children() {
+        for attribute in set.children() {
+            let (Some(oid), Some(value)) = (
+                attribute.child(0).and_then(|a| a.oid()),
+                attribute.child(1).and_then(|a| a.string()),
+            ) else {
+                continue;
+            };
+            if let Some((_, key)) = NAME_ATTRIBUTES.iter().find(|(a, _)| *a == oid) {
+                parts.push(format!("{key}={value}"));
+            }
+        }
+    }
+
+    parts.reverse();
+    parts.join(", ")
+}
+
+fn serial(serial: &Element) -> String {
+    let bytes = serial.contents;
+    let start = bytes.iter().position(|a| *a != 0).unwrap_or(bytes.len());
+
+    bytes[start..].iter().map(|a| format!("{a:02X}")).collect()
+}
+
+fn digest_algorithm(algorithm: &Element) -> Option<String> {
+    let oid = algorithm.child(0)?.oid()?;
+    Some(
+        DIGEST_ALGORITHMS
+            .iter()
+            .find(|(a, _)| *a == oid)
+            .map_or(oid, |(_, a)| a.to_string()),
+    )
+}
+
+/// Values of the attributes with the given type in an `Attributes` set.
+fn attribute_values<'a>(attributes: &Element<'a>, kind: &str) -> Vec<Element<'a>> {
+    attributes
+        .children()
+        .iter()
+        .filter(|a| a.child(0).and_then(|a| a.oid()).as_deref() == Some(kind))
+        .filter_map(|a| a.child(1))
+        .flat_map(|a| a.children())
+        .collect()
+}
+
+/// Signing time from the authenticated attributes of a legacy countersignature.
+fn countersignature_time(signer_info: &Element) -> Option<DateTime<Utc>> {
+    let attributes = signer_info
+        .children()
+        .into_iter()
+        .find(|a| a.tag == CONTEXT_0)?;
+
+    attribute_values(&attributes, SIGNING_TIME).first()?.time()
+}
+
+/// `genTime` from the `TSTInfo` of an RFC 3161 timestamp token.
+fn rfc3161_time(content_info: &Element) -> Option<DateTime<Utc>> {
+    let signed_data = signed_data(content_info)?;
+    let encapsulated = signed_data.child(2)?;
+    let content = encapsulated.child(1).filter(|a| a.tag == CONTEXT_0)?;
+    let octets = content.child(0).filter(|a| a.tag == OCTET_STRING)?;
+    let (tst_info, _) = Element::parse(octets.contents)?;
+
+    tst_info
+        .children()
+        .into_iter()
+        .find(|a| a.tag == GENERALIZED_TIME)?
+        .time()
+}
+
+/// The `messageDigest` of the `SpcIndirectDataContent`, which is the authentihash of the image when it was signed.
+fn image_digest(signed_data: &Element) -> Option<(String, Vec<u8>)> {
+    let encapsulated = signed_data.child(2)?;
+    if encapsulated.child(0)?.oid()? != SPC_INDIRECT_DATA {
+        return None;
+    }
+
+    let content = encapsulated.child(1).filter(|a| a.tag == CONTEXT_0)?;
+    let digest_info = content.child(0)?.child(1)?;
+    let digest = digest_info.child(1).filter(|a| a.tag == OCTET_STRING)?;
+
+    Some((
+        digest_algorithm(&digest_info.child(0)?)?,
+        digest.contents.to_vec(),
+    ))
+}
+
+fn parse_signature(data: &[u8]) -> Option<(Signature, usize)> {
+    let (content_info, _) = Element::parse(data)?;
+    let signed_data = signed_data(&content_info)?;
+    let children = signed_data.children();
+
+    let certificates = children
+        .iter()
+        .find(|a| a.tag == CONTEXT_0)
+        .map(|a| a.children())
+        .unwrap_or_default();
+    let signer_info = children.iter().rfind(|a| a.tag == SET)?.child(0)?;
+    let signer = signer_info.children();
+
+    let issuer_and_serial = signer.get(1)?;
+    let issuer = issuer_and_serial.child(0)?;
+    let serial_number = issuer_and_serial.child(1).filter(|a| a.tag == INTEGER)?;
+    let digest_algorithm = digest_algorithm(signer.get(2)?)?;
+
+    // The signer certificate is the one with the same issuer and serial number.
+    let certificate = certificates.iter().find_map(|certificate| {
+        let tbs = certificate.child(0)?.children();
+        let offset = usize::from(tbs.first()?.tag == CONTEXT_0);
+        (tbs.get(offset)?.raw == serial_number.raw && tbs.get(offset + 2)?.raw == issuer.raw)
+            .then(|| tbs[offset..].to_vec())
+    });
+    let (subject, valid_from, valid_to) = match &certificate {
+        None => (None, None, None),
+        Some(tbs) => {
+            let validity = tbs.get(3).map(|a| a.children()).unwrap_or_default();
+            (
+                tbs.get(4).map(name),
+                validity.first().and_then(|a| a.time()),
+                validity.get(1).and_then(|a| a.time()),
+            )
+        }
+    };
+
+    let unauthenticated = signer.iter().find(|a| a.tag == CONTEXT_1);
+    let (signing_time, timestamp_kind) = match unauthenticated {
+        None => (None, None),
+        Some(attributes) => {
+            if let Some(time) = attribute_values(attributes, RFC3161_COUNTERSIGNATURE)
+                .first()
+                .and_then(rfc3161_time)
+            {
+                (Some(time), Some("RFC 3161"))
+            } else if let Some(time) = attribute_values(attributes, COUNTERSIGNATURE)
+                .first()
+                .and_then(countersignature_time)
+            {
+                (Some(time), Some("Authenticode"))
+            } else {
+                (None, None)
+            }
+        }
+    };
+    let nested = unauthenticated.map_or(0, |a| attribute_values(a, NESTED_SIGNATURE).len());
+
+    Some((
+        Signature {
+            subject,
+            issuer: name(&issuer),
+            serial: serial(&serial_number),
+            digest_algorithm,
+            signing_time,
+            timestamp_kind,
+            valid_from,
+            valid_to,
+            image_digest: image_digest(&signed_data),
+        },
+        nested,
+    ))
+}
+
+/// The PKCS#7 blob of the first `WIN_CERTIFICATE`, the security directory points to a file offset.
+fn certificate(pe: &PtrPE) -> Option<&[u8]> {
+    let directory = pe.get_data_directory(ImageDirectoryEntry::Security).ok()?;
+    let offset = directory.virtual_address.0 as usize;
+    if offset == 0 {
+        return None;
+    }
+    let table = pe
+        .as_slice()
+        .get(offset..offset.checked_add(directory.size as usize)?)?;
+
+    let length = read_u32(table, 0)? as usize;
+    if read_u16(table, 6)? != WIN_CERT_TYPE_PKCS_SIGNED_DATA {
+        return None;
+    }
+
+    table.get(8..length)
+}
+
+pub fn versions_from_authenticode(pe: &PtrPE, info: &mut Info, args: &Args) {
+    let Some(data) = certificate(pe) else {
+        return;
+    };
+    let Some((signature, nested)) = parse_signature(data) else {
+        print_self_reported(
+            "Image has an Authenticode signature that could not be parsed",
+            args.quiet,
+        );
+        return;
+    };
+
+    print_self_reported(
+        format!(
+            "Authenticode signature with {} digest by '{}' issued by '{}' with serial {}",
+            signature.digest_algorithm,
+            signature.subject.as_deref().unwrap_or("unknown signer"),
+            signature.issuer,
+            signature.serial
+        ),
+        args.quiet,
+    );
+    if nested != 0 {
+        print_self_reported(
+            format!("Authenticode signature has {nested} nested signatures"),
+            args.quiet,
+        );
+    }
+
+    match (signature.signing_time, signature.timestamp_kind) {
+        (Some(signing_time), Some(kind)) => print_self_reported(
+            format!("{kind} countersignature has signing time {signing_time}"),
+            args.quiet,
+        ),
+        _ => print_self_reported(
+            "Authenticode signature has no countersignature, so the signing time is unknown",
+            args.quiet,
+        ),
+    }
+
+    // The image has to be built before it is signed.
+    if let Some(signing_time) = signature.signing_time {
+        info.add_to_timeline(signing_time, "Authenticode signing time".to_string());
+
+        match info.timestamp() {
+            Some(timestamp) if signing_time < timestamp => {
+                info.set_likely_incorrect(LikelyIncorrect::SignedBeforeTimestamp);
+                print_heuristic(
+                    format!("Signing time {signing_time} is before the timestamp, which means the timestamp was modified or is not a real date"),
+                    args.quiet,
+                );
+            }
+            Some(_) => {}
+            None => {
+                let newest = CompilerVersion::newest_possible(signing_time);
+                print_heuristic(
+                    format!("Signing time {signing_time} is the latest the image can have been built, which means the newest possible version is {newest}"),
+                    args.quiet,
+                );
+                info.exclude_compiler_versions_after(newest);
+            }
+        }
+    }
+
+    // Certificates are only valid for signing after they are issued, although an image can be signed again later.
+    if let Some(valid_from) = signature.valid_from {
+        info.add_to_timeline(valid_from, "Signer certificate valid from".to_string());
+        // Signing certificates are usually issued shortly before the software they sign is released.
+        if info.timestamp().is_none() && info.era().is_none() {
+            print_heuristic(
+                format!(
+                    "Signer certificate is valid from {}, which suggests when the image was built",
+                    valid_from.year()
+                ),
+                args.quiet,
+            );
+            info.set_era(valid_from.year());
+        }
+
+        if !info.possible_at_timestamp(valid_from) {
+            print_heuristic(
+                format!("Signer certificate is valid from {valid_from}, which is after the timestamp, so the image was signed long after it was built or the timestamp is not a real date"),
+                args.quiet,
+            );
+        }
+
+        if let Some(signing_time) = signature.signing_time {
+            let expired = signature.valid_to.is_some_and(|a| signing_time > a);
+            if signing_time < valid_from || expired {
+                print_heuristic(
+                    "Signing time is outside of the validity period of the signer certificate",
+                    args.quiet,
+                );
+            }
+        }
+    }
+
+    info.set_signature(signature);
+}
@@ -0,0 +1,131 @@
+use crate::checksum::checksum_offset;
+use crate::info::Info;
+use crate::likely_incorrect::LikelyIncorrect;
+use crate::read::{read_u16, read_u32};
+use crate::{print_heuristic, print_self_reported, Args};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+const PE32_MAGIC: u16 = 0x10B;
+const PE32_PLUS_MAGIC: u16 = 0x20B;
+const IMAGE_DIRECTORY_ENTRY_SECURITY: usize = 4;
+
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Authentihash {
+    pub sha1: Vec<u8>,
+    pub sha256: Vec<u8>,
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|a| format!("{a:02x}")).collect()
+}
+
+/// Parts of the file that are hashed, which is everything except the checksum, the security directory entry and the certificate table.
+///
+/// The file is hashed in order instead of section by section, which gives the same result as the specification for every image where the sections follow the headers in order.
+fn hashed_ranges(data: &[u8]) -> Option<Vec<&[u8]>> {
+    let checksum = checksum_offset(data)?;
+    let optional_header = checksum - 64;
+
+    let (rva_and_sizes, directories) = match read_u16(data, optional_header)? {
+        PE32_MAGIC => (optional_header + 92, optional_header + 96),
+        PE32_PLUS_MAGIC => (optional_header + 108, optional_header + 112),
+        _ => return None,
+    };
+
+    if read_u32(data, rva_and_sizes)? as usize <= IMAGE_DIRECTORY_ENTRY_SECURITY {
+        return Some(vec![data.get(..checksum)?, data.get(checksum + 4..)?]);
+    }
+
+    let security = directories + IMAGE_DIRECTORY_ENTRY_SECURITY * 8;
+    let certificate_offset = read_u32(data, security)? as usize;
+    let certificate_size = read_u32(data, security + 4)? as usize;
+    let (certificate_start, certificate_end) = if certificate_offset == 0 {
+        (data.len(), data.len())
+    } else {
+        (
+            certificate_offset.min(data.len()),
+            certificate_offset
+                .saturating_add(certificate_size)
+                .min(data.len()),
+        )
+    };
+    if certificate_start < security + 8 {
+        return None;
+    }
+
+    Some(vec![
+        data.get(..checksum)?,
+        data.get(checksum + 4..security)?,
+        data.get(security + 8..certificate_start)?,
+        data.get(certificate_end..)?,
+    ])
+}
+
+fn authentihash(data: &[u8]) -> Option<Authentihash> {
+    let ranges = hashed_ranges(data)?;
+
+    let mut sha1 = Sha1::new();
+    let mut sha256 = Sha256::new();
+    for range in ranges {
+        sha1.update(range);
+        sha256.update(range);
+    }
+
+    Some(Authentihash {
+        sha1: sha1.finalize().to_vec(),
+        sha256: sha256.finalize().to_vec(),
+    })
+}
+
+pub fn versions_from_authentihash(data: &[u8], info: &mut Info, args: &Args) {
+    let Some(hash) = authentihash(data) else {
+        return;
+    };
+
+    print_self_reported(
+        format!(
+            "Authentihash is SHA-1 {} and SHA-256 {}",
+            hex(&hash.sha1),
+            hex(&hash.sha256)
+        ),
+        args.quiet,
+    );
+
+    let image_digest = info
+        .signature()
+        .and_then(|a| a.image_digest.as_ref())
+        .cloned();
+    if let Some((algorithm, digest)) = image_digest {
+        let computed = match algorithm.as_str() {
+            "SHA-1" => Some(&hash.sha1),
+            "SHA-256" => Some(&hash.sha256),
+            _ => None,
+        };
+
+        match computed {
+            None => print_self_reported(
+                format!(
+                    "Signature digest uses {algorithm}, so it can not be compared with the image"
+                ),
+                args.quiet,
+            ),
+            Some(computed) if *computed == digest => print_self_reported(
+                format!("Signature digest matches image ({algorithm})"),
+                args.quiet,
+            ),
+            Some(_) => {
+                info.set_likely_incorrect(LikelyIncorrect::ModifiedAfterSigning);
+                print_heuristic(
+                    format!(
+                        "Image modified after signing, the signature digest {} does not match the authentihash",
+                        hex(&digest)
+                    ),
+                    args.quiet,
+                );
+            }
+        }
+    }
+
+    info.set_authentihash(hash);
+}
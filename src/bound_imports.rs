@@ -0,0 +1,127 @@
+use crate::info::Info;
+use crate::read::{read_cstring, read_u16, read_u32};
+use crate::timestamps::{is_reproducible, DELPHI_TIMESTAMP};
+use crate::{print_heuristic, print_self_reported, Args};
+use chrono::{DateTime, Utc};
+use exe::{Buffer, ImageDirectoryEntry, PtrPE, PE};
+
+const IMAGE_BOUND_IMPORT_DESCRIPTOR_SIZE: usize = 8;
+
+/// A DLL that the imports were bound to, with the `TimeDateStamp` it had at the time.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct BoundImport {
+    pub name: String,
+    pub timestamp: u32,
+    /// DLLs that forwarded exports were bound to, such as NTDLL for KERNEL32.
+    pub forwarders: Vec<(String, u32)>,
+}
+
+/// Name and timestamp of a descriptor, names are offsets from the start of the directory.
+fn descriptor(data: &[u8], offset: usize) -> Option<(String, u32, u16)> {
+    let timestamp = read_u32(data, offset)?;
+    let name = read_u16(data, offset + 4)?;
+    let forwarders = read_u16(data, offset + 6)?;
+    if timestamp == 0 && name == 0 {
+        return None;
+    }
+
+    Some((read_cstring(data, name as usize)?, timestamp, forwarders))
+}
+
+/// The bound import directory, which is at a file offset instead of an RVA.
+pub fn parse_bound_imports(pe: &PtrPE) -> Vec<BoundImport> {
+    let Ok(directory) = pe.get_data_directory(ImageDirectoryEntry::BoundImport) else {
+        return Vec::new();
+    };
+    let start = directory.virtual_address.0 as usize;
+    let Some(data) = pe
+        .as_slice()
+        .get(start..start.saturating_add(directory.size as usize))
+        .filter(|_| start != 0)
+    else {
+        return Vec::new();
+    };
+
+    let mut imports = Vec::new();
+    let mut offset = 0;
+    while let Some((name, timestamp, forwarders)) = descriptor(data, offset) {
+        // Forwarder references follow their descriptor and have the same size.
+        let forwarders = (1..=forwarders as usize)
+            .filter_map(|a| descriptor(data, offset + a * IMAGE_BOUND_IMPORT_DESCRIPTOR_SIZE))
+            .map(|(name, timestamp, _)| (name, timestamp))
+            .collect::<Vec<_>>();
+        offset += IMAGE_BOUND_IMPORT_DESCRIPTOR_SIZE * (1 + forwarders.len());
+
+        imports.push(BoundImport {
+            name,
+            timestamp,
+            forwarders,
+        });
+    }
+
+    imports
+}
+
+fn unix_time(value: u32) -> DateTime<Utc> {
+    DateTime::from_timestamp(value.into(), 0).unwrap()
+}
+
+pub fn versions_from_bound_imports(pe: &PtrPE, coff: u32, info: &mut Info, args: &Args) {
+    let imports = parse_bound_imports(pe);
+    if imports.is_empty() {
+        return;
+    }
+
+    for import in &imports {
+        print_self_reported(
+            format!(
+                "Imports are bound to {} with timestamp {}",
+                import.name,
+                unix_time(import.timestamp)
+            ),
+            args.quiet,
+        );
+        for (name, timestamp) in &import.forwarders {
+            print_self_reported(
+                format!(
+                    "Imports are bound to {name} with timestamp {} through forwarders in {}",
+                    unix_time(*timestamp),
+                    import.name
+                ),
+                args.quiet,
+            );
+        }
+    }
+
+    // Binding can only have happened once every DLL it was bound to existed.
+    let Some((name, latest)) = imports
+        .iter()
+        .flat_map(|a| {
+            std::iter::once((&a.name, a.timestamp)).chain(
+                a.forwarders
+                    .iter()
+                    .map(|(name, timestamp)| (name, *timestamp)),
+            )
+        })
+        .filter(|(_, timestamp)| *timestamp != 0 && *timestamp != u32::MAX)
+        .max_by_key(|(_, timestamp)| *timestamp)
+    else {
+        return;
+    };
+    let latest = unix_time(latest);
+
+    // A zero, hashed or Delphi timestamp does not say when the image was linked.
+    let linked = coff != 0 && coff != DELPHI_TIMESTAMP && !is_reproducible(pe);
+    if linked && latest > unix_time(coff) {
+        print_heuristic(
+            format!("Imports were bound after {latest}, the timestamp of {name}, which is after the COFF timestamp as expected when binding happens after linking, such as by an installer or BIND.EXE, which is not a sign that the image was modified"),
+            args.quiet,
+        );
+    } else {
+        print_heuristic(
+            format!("Imports were bound after {latest}, the timestamp of {name}"),
+            args.quiet,
+        );
+    }
+    info.set_bound_after(latest);
+}
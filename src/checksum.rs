@@ -0,0 +1,60 @@
+use crate::info::Info;
+use crate::likely_incorrect::LikelyIncorrect;
+use crate::optional_header::OptionalHeader;
+use crate::read::read_u32;
+use crate::{print_heuristic, print_self_reported, Args};
+
+/// File offset of the `CheckSum` field, which is at the same place in PE32 and PE32+ headers.
+pub fn checksum_offset(data: &[u8]) -> Option<usize> {
+    let e_lfanew = read_u32(data, 0x3C)? as usize;
+    e_lfanew.checked_add(24 + 64)
+}
+
+/// The checksum as calculated by `CheckSumMappedFile`, a 16 bit one's complement sum of the file without the field plus the file size.
+fn checksum(data: &[u8], offset: usize) -> u32 {
+    let mut sum = 0_u64;
+    for (i, word) in data.chunks(2).enumerate() {
+        if i * 2 == offset || i * 2 == offset + 2 {
+            continue;
+        }
+
+        let low = word[0] as u64;
+        let high = word.get(1).copied().unwrap_or(0) as u64;
+        sum += low | (high << 8);
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+
+    (sum as u32).wrapping_add(data.len() as u32)
+}
+
+pub fn versions_from_checksum(data: &[u8], header: &OptionalHeader, info: &mut Info, args: &Args) {
+    let Some(offset) = checksum_offset(data).filter(|a| a + 4 <= data.len()) else {
+        return;
+    };
+
+    let stored = header.check_sum;
+    if stored == 0 {
+        print_self_reported(
+            "CheckSum is zero, which means the image was linked without /RELEASE",
+            args.quiet,
+        );
+        if info.driver().is_some_and(|a| !a.user_mode) {
+            print_heuristic(
+                "Drivers need a valid CheckSum to load, which suggests a non-Microsoft linker or a driver that was never loaded",
+                args.quiet,
+            );
+        }
+        return;
+    }
+
+    let calculated = checksum(data, offset);
+    if stored == calculated {
+        print_self_reported(format!("CheckSum {stored:#010x} is valid"), args.quiet);
+    } else {
+        info.set_likely_incorrect(LikelyIncorrect::ChecksumMismatch);
+        print_heuristic(
+            format!("CheckSum {stored:#010x} does not match the calculated {calculated:#010x}, so the image was patched after linking and the timestamp or versions may have been changed too"),
+            args.quiet,
+        );
+    }
+}
@@ -0,0 +1,564 @@
+use crate::read::{read_cstring, read_u16, read_u32, read_u64, read_u8};
+
+const METADATA_SIGNATURE: u32 = 0x424A5342;
+
+const TYPE_REF: usize = 0x01;
+const MEMBER_REF: usize = 0x0A;
+const CUSTOM_ATTRIBUTE: usize = 0x0C;
+const ASSEMBLY: usize = 0x20;
+const ASSEMBLY_REF: usize = 0x23;
+const TABLE_AMOUNT: usize = 0x2D;
+
+const UNUSED: usize = usize::MAX;
+
+#[derive(Debug, Copy, Clone)]
+struct CodedIndex {
+    tag_bits: u32,
+    tables: &'static [usize],
+}
+
+const TYPE_DEF_OR_REF: CodedIndex = CodedIndex {
+    tag_bits: 2,
+    tables: &[0x02, 0x01, 0x1B],
+};
+const HAS_CONSTANT: CodedIndex = CodedIndex {
+    tag_bits: 2,
+    tables: &[0x04, 0x08, 0x17],
+};
+const HAS_CUSTOM_ATTRIBUTE: CodedIndex = CodedIndex {
+    tag_bits: 5,
+    tables: &[
+        0x06, 0x04, 0x01, 0x02, 0x08, 0x09, 0x0A, 0x00, 0x0E, 0x17, 0x14, 0x11, 0x1A, 0x1B, 0x20,
+        0x23, 0x26, 0x27, 0x28, 0x2A, 0x2C, 0x2B,
+    ],
+};
+const HAS_FIELD_MARSHAL: CodedIndex = CodedIndex {
+    tag_bits: 1,
+    tables: &[0x04, 0x08],
+};
+const HAS_DECL_SECURITY: CodedIndex = CodedIndex {
+    tag_bits: 2,
+    tables: &[0x02, 0x06, 0x20],
+};
+const MEMBER_REF_PARENT: CodedIndex = CodedIndex {
+    tag_bits: 3,
+    tables: &[0x02, 0x01, 0x1A, 0x06, 0x1B],
+};
+const HAS_SEMANTICS: CodedIndex = CodedIndex {
+    tag_bits: 1,
+    tables: &[0x14, 0x17],
+};
+const METHOD_DEF_OR_REF: CodedIndex = CodedIndex {
+    tag_bits: 1,
+    tables: &[0x06, 0x0A],
+};
+const MEMBER_FORWARDED: CodedIndex = CodedIndex {
+    tag_bits: 1,
+    tables: &[0x04, 0x06],
+};
+const IMPLEMENTATION: CodedIndex = CodedIndex {
+    tag_bits: 2,
+    tables: &[0x26, 0x23, 0x27],
+};
+const CUSTOM_ATTRIBUTE_TYPE: CodedIndex = CodedIndex {
+    tag_bits: 3,
+    tables: &[UNUSED, UNUSED, 0x06, 0x0A, UNUSED],
+};
+const RESOLUTION_SCOPE: CodedIndex = CodedIndex {
+    tag_bits: 2,
+    tables: &[0x00, 0x1A, 0x23, 0x01],
+};
+const TYPE_OR_METHOD_DEF: CodedIndex = CodedIndex {
+    tag_bits: 1,
+    tables: &[0x02, 0x06],
+};
+
+#[derive(Debug, Copy, Clone)]
+enum Column {
+    Fixed(usize),
+    String,
+    Guid,
+    Blob,
+    Table(usize),
+    Coded(CodedIndex),
+}
+
+/// Column layouts of every table from ECMA-335 II.22 in table number order.
+const SCHEMA: [&[Column]; TABLE_AMOUNT] = {
+    use Column::*;
+    [
+        // Module
+        &[Fixed(2), String, Guid, Guid, Guid],
+        // TypeRef
+        &[Coded(RESOLUTION_SCOPE), String, String],
+        // TypeDef
+        &[
+            Fixed(4),
+            String,
+            String,
+            Coded(TYPE_DEF_OR_REF),
+            Table(0x04),
+            Table(0x06),
+        ],
+        // FieldPtr
+        &[Table(0x04)],
+        // Field
+        &[Fixed(2), String, Blob],
+        // MethodPtr
+        &[Table(0x06)],
+        // MethodDef
+        &[Fixed(4), Fixed(2), Fixed(2), String, Blob, Table(0x08)],
+        // ParamPtr
+        &[Table(0x08)],
+        // Param
+        &[Fixed(2), Fixed(2), String],
+        // InterfaceImpl
+        &[Table(0x02), Coded(TYPE_DEF_OR_REF)],
+        // MemberRef
+        &[Coded(MEMBER_REF_PARENT), String, Blob],
+        // Constant
+        &[Fixed(2), Coded(HAS_CONSTANT), Blob],
+        // CustomAttribute
+        &[
+            Coded(HAS_CUSTOM_ATTRIBUTE),
+            Coded(CUSTOM_ATTRIBUTE_TYPE),
+            Blob,
+        ],
+        // FieldMarshal
+        &[Coded(HAS_FIELD_MARSHAL), Blob],
+        // DeclSecurity
+        &[Fixed(2), Coded(HAS_DECL_SECURITY), Blob],
+        // ClassLayout
+        &[Fixed(2), Fixed(4), Table(0x02)],
+        // FieldLayout
+        &[Fixed(4), Table(0x04)],
+        // StandAloneSig
+        &[Blob],
+        // EventMap
+        &[Table(0x02), Table(0x14)],
+        // EventPtr
+        &[Table(0x14)],
+        // Event
+        &[Fixed(2), String, Coded(TYPE_DEF_OR_REF)],
+        // PropertyMap
+        &[Table(0x02), Table(0x17)],
+        // PropertyPtr
+        &[Table(0x17)],
+        // Property
+        &[Fixed(2), String, Blob],
+        // MethodSemantics
+        &[Fixed(2), Table(0x06), Coded(HAS_SEMANTICS)],
+        // MethodImpl
+        &[
+            Table(0x02),
+            Coded(METHOD_DEF_OR_REF),
+            Coded(METHOD_DEF_OR_REF),
+        ],
+        // ModuleRef
+        &[String],
+        // TypeSpec
+        &[Blob],
+        // ImplMap
+        &[Fixed(2), Coded(MEMBER_FORWARDED), String, Table(0x1A)],
+        // FieldRVA
+        &[Fixed(4), Table(0x04)],
+        // EncLog
+        &[Fixed(4), Fixed(4)],
+        // EncMap
+        &[Fixed(4)],
+        // Assembly
+        &[
+            Fixed(4),
+            Fixed(2),
+            Fixed(2),
+            Fixed(2),
+            Fixed(2),
+            Fixed(4),
+            Blob,
+            String,
+            String,
+        ],
+        // AssemblyProcessor
+        &[Fixed(4)],
+        // AssemblyOS
+        &[Fixed(4), Fixed(4), Fixed(4)],
+        // AssemblyRef
+        &[
+            Fixed(2),
+            Fixed(2),
+            Fixed(2),
+            Fixed(2),
+            Fixed(4),
+            Blob,
+            String,
+            String,
+            Blob,
+        ],
+        // AssemblyRefProcessor
+        &[Fixed(4), Table(0x23)],
+        // AssemblyRefOS
+        &[Fixed(4), Fixed(4), Fixed(4), Table(0x23)],
+        // File
+        &[Fixed(4), String, Blob],
+        // ExportedType
+        &[Fixed(4), Fixed(4), String, String, Coded(IMPLEMENTATION)],
+        // ManifestResource
+        &[Fixed(4), Fixed(4), String, Coded(IMPLEMENTATION)],
+        // NestedClass
+        &[Table(0x02), Table(0x02)],
+        // GenericParam
+        &[Fixed(2), Fixed(2), Coded(TYPE_OR_METHOD_DEF), String],
+        // MethodSpec
+        &[Coded(METHOD_DEF_OR_REF), Blob],
+        // GenericParamConstraint
+        &[Table(0x2A), Coded(TYPE_DEF_OR_REF)],
+    ]
+};
+
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct AssemblyVersion {
+    pub major: u16,
+    pub minor: u16,
+    pub build: u16,
+    pub revision: u16,
+}
+
+impl std::fmt::Display for AssemblyVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}.{}",
+            self.major, self.minor, self.build, self.revision
+        )
+    }
+}
+
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct AssemblyReference {
+    pub name: String,
+    pub version: AssemblyVersion,
+}
+
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct AssemblyDefinition {
+    pub name: String,
+    pub version: AssemblyVersion,
+    pub has_public_key: bool,
+}
+
+/// Parsed metadata root with the `#~` table stream.
+pub struct Metadata<'a> {
+    pub version: String,
+    strings: &'a [u8],
+    blobs: &'a [u8],
+    tables: &'a [u8],
+    rows: [u32; TABLE_AMOUNT],
+    table_offsets: [usize; TABLE_AMOUNT],
+    large_strings: bool,
+    large_guids: bool,
+    large_blobs: bool,
+}
+
+impl<'a> Metadata<'a> {
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        if read_u32(data, 0)? != METADATA_SIGNATURE {
+            return None;
+        }
+
+        let version_length = read_u32(data, 12)? as usize;
+        let version = data.get(16..16 + version_length)?;
+        let version = String::from_utf8_lossy(version)
+            .trim_end_matches('\0')
+            .to_string();
+
+        let mut offset = 16 + version_length;
+        let stream_amount = read_u16(data, offset + 2)?;
+        offset += 4;
+
+        let mut strings: &[u8] = &[];
+        let mut blobs: &[u8] = &[];
+        let mut tables: Option<&[u8]> = None;
+
+        for _ in 0..stream_amount {
+            let stream_offset = read_u32(data, offset)? as usize;
+            let stream_size = read_u32(data, offset + 4)? as usize;
+            let name_start = offset + 8;
+            let name_length = data.get(name_start..)?.iter().position(|a| *a == 0)?;
+            let name = &data[name_start..name_start + name_length];
+
+            let stream = data.get(stream_offset..stream_offset.checked_add(stream_size)?)?;
+            match name {
+                b"#Strings" => strings = stream,
+                b"#Blob" => blobs = stream,
+                b"#~" | b"#-" => tables = Some(stream),
+                _ => {}
+            }
+
+            // Name is null terminated and padded to four bytes.
+            offset = name_start + (name_length + 4) / 4 * 4;
+        }
+
+        let tables = tables?;
+        let heap_sizes = read_u8(tables, 6)?;
+        let valid = read_u64(tables, 8)?;
+
+        let mut offset = 24;
+        let mut rows = [0_u32; TABLE_AMOUNT];
+        for i in 0..64 {
+            if valid & (1 << i) == 0 {
+                continue;
+            }
+
+            let amount = read_u32(tables, offset)?;
+            offset += 4;
+
+            if let Some(row) = rows.get_mut(i) {
+                *row = amount;
+            }
+        }
+
+        // Uncompressed streams may contain an extra 4 bytes of data after the row counts.
+        if heap_sizes & 0x40 != 0 {
+            offset += 4;
+        }
+
+        let mut metadata = Self {
+            version,
+            strings,
+            blobs,
+            tables,
+            rows,
+            table_offsets: [0; TABLE_AMOUNT],
+            large_strings: heap_sizes & 0x01 != 0,
+            large_guids: heap_sizes & 0x02 != 0,
+            large_blobs: heap_sizes & 0x04 != 0,
+        };
+
+        for table in 0..TABLE_AMOUNT {
+            metadata.table_offsets[table] = offset;
+            offset += metadata.row_size(table) * metadata.rows[table] as usize;
+        }
+
+        Some(metadata)
+    }
+
+    fn index_size(&self, table: usize) -> usize {
+        if self.rows[table] > u16::MAX as u32 {
+            4
+        } else {
+            2
+        }
+    }
+
+    fn coded_index_size(&self, coded: CodedIndex) -> usize {
+        let max_rows = coded
+            .tables
+            .iter()
+            .filter(|a| **a != UNUSED)
+            .map(|a| self.rows[*a])
+            .max()
+            .unwrap_or(0);
+
+        if max_rows >= (1 << (16 - coded.tag_bits)) {
+            4
+        } else {
+            2
+        }
+    }
+
+    fn column_size(&self, column: Column) -> usize {
+        let large = |large: bool| if large { 4 } else { 2 };
+
+        match column {
+            Column::Fixed(size) => size,
+            Column::String => large(self.large_strings),
+            Column::Guid => large(self.large_guids),
+            Column::Blob => large(self.large_blobs),
+            Column::Table(table) => self.index_size(table),
+            Column::Coded(coded) => self.coded_index_size(coded),
+        }
+    }
+
+    fn row_size(&self, table: usize) -> usize {
+        SCHEMA[table].iter().map(|a| self.column_size(*a)).sum()
+    }
+
+    /// Read a column of a row, `row` is 1-indexed like metadata tokens.
+    fn column(&self, table: usize, row: u32, column: usize) -> Option<u32> {
+        if row == 0 || row > self.rows[table] {
+            return None;
+        }
+
+        let mut offset = self.table_offsets[table] + (row as usize - 1) * self.row_size(table);
+        for c in &SCHEMA[table][..column] {
+            offset += self.column_size(*c);
+        }
+
+        match self.column_size(SCHEMA[table][column]) {
+            1 => read_u8(self.tables, offset).map(u32::from),
+            2 => read_u16(self.tables, offset).map(u32::from),
+            4 => read_u32(self.tables, offset),
+            _ => None,
+        }
+    }
+
+    /// Splits a coded index into the table it refers to and the row.
+    fn decode(coded: CodedIndex, value: u32) -> Option<(usize, u32)> {
+        let tag = value & ((1 << coded.tag_bits) - 1);
+        let table = *coded.tables.get(tag as usize)?;
+        if table == UNUSED {
+            return None;
+        }
+
+        Some((table, value >> coded.tag_bits))
+    }
+
+    fn string(&self, index: u32) -> Option<String> {
+        read_cstring(self.strings, index as usize)
+    }
+
+    fn blob(&self, index: u32) -> Option<&'a [u8]> {
+        let (length, size) = read_compressed_u32(self.blobs.get(index as usize..)?)?;
+        let start = index as usize + size;
+
+        self.blobs.get(start..start + length as usize)
+    }
+
+    fn version(&self, table: usize, row: u32, first_column: usize) -> Option<AssemblyVersion> {
+        Some(AssemblyVersion {
+            major: self.column(table, row, first_column)? as u16,
+            minor: self.column(table, row, first_column + 1)? as u16,
+            build: self.column(table, row, first_column + 2)? as u16,
+            revision: self.column(table, row, first_column + 3)? as u16,
+        })
+    }
+
+    pub fn assembly(&self) -> Option<AssemblyDefinition> {
+        let public_key = self.column(ASSEMBLY, 1, 6)?;
+
+        Some(AssemblyDefinition {
+            name: self.string(self.column(ASSEMBLY, 1, 7)?)?,
+            version: self.version(ASSEMBLY, 1, 1)?,
+            has_public_key: self.blob(public_key).is_some_and(|a| !a.is_empty()),
+        })
+    }
+
+    pub fn assembly_references(&self) -> Vec<AssemblyReference> {
+        (1..=self.rows[ASSEMBLY_REF])
+            .filter_map(|row| {
+                Some(AssemblyReference {
+                    name: self.string(self.column(ASSEMBLY_REF, row, 6)?)?,
+                    version: self.version(ASSEMBLY_REF, row, 0)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Name of the type the constructor of a custom attribute belongs to.
+    fn attribute_type_name(&self, row: u32) -> Option<String> {
+        let constructor = self.column(CUSTOM_ATTRIBUTE, row, 1)?;
+        let (table, constructor) = Self::decode(CUSTOM_ATTRIBUTE_TYPE, constructor)?;
+        if table != MEMBER_REF {
+            return None;
+        }
+
+        let parent = self.column(MEMBER_REF, constructor, 0)?;
+        let (table, parent) = Self::decode(MEMBER_REF_PARENT, parent)?;
+        if table != TYPE_REF {
+            return None;
+        }
+
+        let name = self.string(self.column(TYPE_REF, parent, 1)?)?;
+        let namespace = self.string(self.column(TYPE_REF, parent, 2)?)?;
+
+        Some(format!("{namespace}.{name}"))
+    }
+
+    /// Fixed string arguments of every custom attribute placed on the assembly with the given type name.
+    fn assembly_attribute_strings(&self, type_name: &str) -> Vec<String> {
+        let mut strings = Vec::new();
+
+        for row in 1..=self.rows[CUSTOM_ATTRIBUTE] {
+            let Some(parent) = self.column(CUSTOM_ATTRIBUTE, row, 0) else {
+                continue;
+            };
+            if Self::decode(HAS_CUSTOM_ATTRIBUTE, parent) != Some((ASSEMBLY, 1)) {
+                continue;
+            }
+
+            if self.attribute_type_name(row).as_deref() != Some(type_name) {
+                continue;
+            }
+
+            let Some(value) = self
+                .column(CUSTOM_ATTRIBUTE, row, 2)
+                .and_then(|a| self.blob(a))
+            else {
+                continue;
+            };
+
+            // Prolog is always 0x0001, followed by a SerString.
+            if value.get(..2) != Some(&[0x01, 0x00]) {
+                continue;
+            }
+            let Some((length, size)) = read_compressed_u32(&value[2..]) else {
+                continue;
+            };
+            let start = 2 + size;
+            if let Some(s) = value.get(start..start + length as usize) {
+                strings.push(String::from_utf8_lossy(s).into_owned());
+            }
+        }
+
+        strings
+    }
+
+    /// Value of `System.Runtime.Versioning.TargetFrameworkAttribute`, like `.NETFramework,Version=v4.8`.
+    pub fn target_framework(&self) -> Option<String> {
+        self.assembly_attribute_strings("System.Runtime.Versioning.TargetFrameworkAttribute")
+            .into_iter()
+            .next()
+    }
+}
+
+/// Reads an ECMA-335 II.23.2 compressed unsigned integer, returning the value and its size in bytes.
+fn read_compressed_u32(data: &[u8]) -> Option<(u32, usize)> {
+    let first = *data.first()? as u32;
+
+    if first & 0x80 == 0 {
+        Some((first, 1))
+    } else if first & 0xC0 == 0x80 {
+        Some((((first & 0x3F) << 8) | *data.get(1)? as u32, 2))
+    } else if first & 0xE0 == 0xC0 {
+        let rest = data.get(1..4)?;
+        Some((
+            ((first & 0x1F) << 24)
+                | (rest[0] as u32) << 16
+                | (rest[1] as u32) << 8
+                | rest[2] as u32,
+            4,
+        ))
+    } else {
+        None
+    }
+}
+
+/// Converts a `TargetFrameworkAttribute` value into a target framework moniker like `net48` or `net8.0`.
+pub fn target_framework_moniker(framework: &str) -> Option<String> {
+    let (identifier, version) = framework.split_once(",Version=v")?;
+    let version = version.split(',').next()?;
+
+    Some(match identifier {
+        ".NETFramework" => format!("net{}", version.replace('.', "")),
+        ".NETStandard" => format!("netstandard{version}"),
+        ".NETCoreApp" => {
+            let major = version.split('.').next()?.parse::<u32>().ok()?;
+            if major >= 5 {
+                format!("net{version}")
+            } else {
+                format!("netcoreapp{version}")
+            }
+        }
+        _ => return None,
+    })
+}
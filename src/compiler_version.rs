@@ -3,6 +3,8 @@ use std::fmt::Formatter;
 
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub enum CompilerVersion {
+    MicrosoftC6_0,
+    MicrosoftC7_0,
     VisualCPP1_0,
     VisualCPP1_5,
     VisualCPP2_0,
@@ -27,6 +29,8 @@ pub enum CompilerVersion {
 impl std::fmt::Display for CompilerVersion {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
+            CompilerVersion::MicrosoftC6_0 => f.write_str("Microsoft C 6.0"),
+            CompilerVersion::MicrosoftC7_0 => f.write_str("Microsoft C/C++ 7.0"),
             CompilerVersion::VisualCPP1_0 => f.write_str("Visual C++ 1.0"),
             CompilerVersion::VisualCPP1_5 => f.write_str("Visual C++ 1.5"),
             CompilerVersion::VisualCPP2_0 => f.write_str("Visual C++ 2.0"),
@@ -50,7 +54,7 @@ impl std::fmt::Display for CompilerVersion {
     }
 }
 
-fn date_to_datetime(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+pub fn date_to_datetime(year: i32, month: u32, day: u32) -> DateTime<Utc> {
     DateTime::from_naive_utc_and_offset(
         NaiveDateTime::new(
             match NaiveDate::from_ymd_opt(year, month, day) {
@@ -66,6 +70,9 @@ fn date_to_datetime(year: i32, month: u32, day: u32) -> DateTime<Utc> {
 impl CompilerVersion {
     pub fn release_date(&self) -> DateTime<Utc> {
         match self {
+            // Only the release year is known for the Microsoft C compilers.
+            CompilerVersion::MicrosoftC6_0 => date_to_datetime(1989, 1, 1),
+            CompilerVersion::MicrosoftC7_0 => date_to_datetime(1992, 1, 1),
             CompilerVersion::VisualCPP1_0 => date_to_datetime(1993, 2, 22),
             CompilerVersion::VisualCPP1_5 => date_to_datetime(1993, 12, 1),
             CompilerVersion::VisualCPP2_0 => date_to_datetime(1994, 9, 16),
@@ -89,7 +96,7 @@ impl CompilerVersion {
     }
 
     pub fn newest_possible(datetime: DateTime<Utc>) -> Self {
-        let mut newest = Self::VisualCPP1_0;
+        let mut newest = Self::MicrosoftC6_0;
 
         for v in Self::values() {
             if v.release_date() > datetime {
@@ -104,6 +111,8 @@ impl CompilerVersion {
 
     fn values() -> &'static [Self] {
         &[
+            Self::MicrosoftC6_0,
+            Self::MicrosoftC7_0,
             Self::VisualCPP1_0,
             Self::VisualCPP1_5,
             Self::VisualCPP2_0,
@@ -1,34 +1,210 @@
+use crate::authentihash::hex;
 use crate::info::Info;
 use crate::Args;
+use std::fmt::Display;
+
+fn print_one_of(name: &str, values: &[impl Display]) {
+    match values {
+        [] => {
+            println!("{name}: ?");
+        }
+        [single] => {
+            println!("{name}: {single}");
+        }
+        all => {
+            println!("{name} (one of):");
+            for value in all {
+                println!("         {value}")
+            }
+        }
+    }
+}
 
 pub fn print_conclusion(info: Info, args: &Args) -> bool {
     if !info.likely_correct() {
         println!(
             "THIS IS LIKELY INCORRECT EITHER BECAUSE IT WAS PARSED INCORRECTLY OR DELIBERATELY MODIFIED"
         );
+        for reason in info.likely_incorrect_reasons() {
+            println!("Likely Incorrect Because: {reason}");
+        }
     }
 
-    println!("Compiled: {}", info.timestamp());
+    match info.timestamp() {
+        None => match info.era() {
+            None => println!("Compiled: ?"),
+            Some(year) => println!("Compiled: ? (around {year} or later)"),
+        },
+        Some(timestamp) => println!("Compiled: {timestamp}"),
+    }
 
-    match info.compiler_version().as_slice() {
-        [] => {
-            println!("Compiler: ?");
+    let timeline = info.timeline();
+    if timeline.len() > 1 {
+        println!("Timeline:");
+        for (date, source) in timeline {
+            println!("         {date} {source}");
         }
-        [single] => {
-            println!("Compiler: {single}");
+    }
+
+    let toolchains = info.toolchains();
+    if toolchains.is_empty() {
+        print_one_of("Compiler", &info.compiler_version());
+    } else {
+        print_one_of("Compiler", &toolchains);
+
+        let compiler_versions = info.compiler_version();
+        if !compiler_versions.is_empty() {
+            print_one_of("Visual C++ Runtime", &compiler_versions);
         }
-        all => {
-            println!("Compiler (one of):");
-            for cv in all {
-                println!("         {cv}")
+    }
+
+    if let Some(machine) = info.machine() {
+        match info.hybrid_code() {
+            None => println!("Architecture: {machine}"),
+            Some(code) => {
+                println!("Architecture: {machine} (hybrid)");
+                println!("         ARM64 code: {} bytes", code.arm64);
+                println!("         ARM64EC code: {} bytes", code.arm64ec);
+                println!("         x64 code: {} bytes", code.x64);
             }
         }
     }
 
+    if let Some(build) = info.crt_build() {
+        println!(
+            "Side-by-side CRT: {} ({} {})",
+            build.version, build.compiler, build.release
+        );
+    }
+
     if let Some(cv) = info.linker_version() {
         println!("Linker: {cv}");
     }
 
+    if let Some(dotnet) = info.dotnet() {
+        if let Some(version) = &dotnet.metadata_version {
+            println!(".NET Runtime: {version}");
+        }
+
+        if let Some(target_framework) = &dotnet.target_framework {
+            println!("Target Framework: {target_framework}");
+        }
+
+        println!("Assembly: {}", dotnet.kind);
+
+        if dotnet.strong_name_signed {
+            println!("Strong-name signed: Yes");
+        }
+    }
+
+    if let Some(visual_basic) = info.visual_basic() {
+        let compilation = if visual_basic.p_code {
+            "P-code"
+        } else {
+            "native code"
+        };
+        let forms = visual_basic.forms;
+
+        match &visual_basic.project_name {
+            None => println!("Visual Basic Project: {compilation}, {forms} forms"),
+            Some(name) => {
+                println!("Visual Basic Project: '{name}', {compilation}, {forms} forms")
+            }
+        }
+    }
+
+    match (info.is_cpp(), info.is_fortran()) {
+        (true, true) => println!("Languages: C++ and Fortran"),
+        (false, true) => println!("Language: Fortran"),
+        _ => {}
+    }
+
+    if let Some(driver) = info.driver() {
+        let mode = if driver.user_mode {
+            "User mode"
+        } else {
+            "Kernel mode"
+        };
+        match &driver.framework {
+            None => println!("Driver: {mode} (WDM)"),
+            Some(framework) => println!("Driver: {mode} ({framework})"),
+        }
+        if let Some(driver_kit) = driver.driver_kit {
+            println!("Driver Kit: {driver_kit}");
+        }
+        if let Some(os) = driver.minimum_from_imports {
+            println!("Kernel Routines: At least {os}");
+        }
+    }
+
+    if let Some(exports) = info.exports() {
+        if let Some(dll_name) = &exports.dll_name {
+            println!("Export Name: {dll_name}");
+        }
+        println!("Exports: {}", exports.exports.len());
+    }
+
+    if let Some(date) = info.bound_after() {
+        println!("Imports Bound After: {date}");
+    }
+
+    if let Some(subsystem) = info.efi_subsystem() {
+        println!("Subsystem: {subsystem}");
+    }
+
+    if let Some(manifest) = info.manifest() {
+        match (manifest.supported_os.first(), manifest.supported_os.last()) {
+            (Some(lowest), Some(highest)) if lowest == highest => {
+                println!("Supported Operating System: {lowest}")
+            }
+            (Some(lowest), Some(highest)) => {
+                println!("Supported Operating Systems: {lowest} to {highest}")
+            }
+            _ => {}
+        }
+        if let Some(level) = &manifest.execution_level {
+            println!("Execution Level: {level}");
+        }
+        if let Some(dpi_awareness) = &manifest.dpi_awareness {
+            println!("DPI Awareness: {dpi_awareness}");
+        }
+        if manifest.long_path_aware {
+            println!("Long Path Aware: Yes");
+        }
+    }
+
+    if let Some(version_info) = info.version_info() {
+        println!("File Version: {}", version_info.file_version);
+        if version_info.product_version != version_info.file_version {
+            println!("Product Version: {}", version_info.product_version);
+        }
+        for (name, value) in &version_info.strings {
+            println!("{name}: {value}");
+        }
+    }
+
+    if info.is_debug_build() {
+        println!("Build Configuration: Debug");
+    }
+
+    if let Some(signature) = info.signature() {
+        if let Some(subject) = &signature.subject {
+            println!("Signed By: {subject}");
+        }
+        println!("Certificate Issuer: {}", signature.issuer);
+        println!("Certificate Serial: {}", signature.serial);
+        println!("Signature Digest: {}", signature.digest_algorithm);
+        match signature.signing_time {
+            None => println!("Signing Time: ?"),
+            Some(signing_time) => println!("Signing Time: {signing_time}"),
+        }
+    }
+
+    if let Some(authentihash) = info.authentihash() {
+        println!("Authentihash SHA-1: {}", hex(&authentihash.sha1));
+        println!("Authentihash SHA-256: {}", hex(&authentihash.sha256));
+    }
+
     if let Some(standard) = info.standard() {
         println!("C++ Standard used: At least {standard}")
     }
@@ -0,0 +1,79 @@
+use crate::compiler_version::CompilerVersion;
+use crate::demangle::Symbol;
+use crate::info::Info;
+use crate::machine::Machine;
+use crate::print_heuristic;
+use crate::Args;
+
+/// Scopes of the Microsoft C++ libraries and the first version that has them.
+const LIBRARY_SCOPES: &[(&str, CompilerVersion)] = &[
+    // The iterator debugging base classes were rewritten for Visual Studio 2010.
+    ("std::_Container_base12", CompilerVersion::VisualStudio2010),
+    ("std::_Iterator_base12", CompilerVersion::VisualStudio2010),
+    // The Concurrency Runtime was introduced with Visual Studio 2010.
+    ("Concurrency::", CompilerVersion::VisualStudio2010),
+];
+
+/// Library and ABI evidence from demangled symbols, `origin` says where they came from such as `Exported`.
+pub fn versions_from_cpp_symbols(symbols: &[Symbol], origin: &str, info: &mut Info, args: &Args) {
+    if symbols.is_empty() {
+        return;
+    }
+
+    for (scope, version) in LIBRARY_SCOPES {
+        let Some(symbol) = symbols.iter().find(|a| a.name.contains(scope)) else {
+            continue;
+        };
+
+        print_heuristic(
+            format!("{origin} symbol '{symbol}' uses {scope}, which suggests at least {version}"),
+            args.quiet,
+        );
+        info.exclude_compiler_versions_before(*version);
+    }
+
+    let mut calling_conventions = symbols
+        .iter()
+        .filter_map(|a| a.calling_convention)
+        .collect::<Vec<_>>();
+    calling_conventions.sort();
+    calling_conventions.dedup();
+    if !calling_conventions.is_empty() {
+        print_heuristic(
+            format!(
+                "{origin} C++ functions use {}",
+                calling_conventions.join(", ")
+            ),
+            args.quiet,
+        );
+    }
+
+    // Functions compiled to MSIL are only mangled like this by C++/CLI.
+    if let Some(symbol) = symbols.iter().find(|a| a.managed.is_some()) {
+        print_heuristic(
+            format!("{origin} symbol '{symbol}' is compiled to MSIL, which means C++/CLI was used with /clr"),
+            args.quiet,
+        );
+    }
+
+    let machine = info.machine();
+    if calling_conventions.contains(&"__thiscall") && machine.is_some_and(|a| a != Machine::X86) {
+        print_heuristic(
+            format!("{origin} C++ functions use __thiscall, which only exists for x86"),
+            args.quiet,
+        );
+    }
+
+    let ptr64 = symbols.iter().any(|a| a.ptr64);
+    match machine {
+        Some(Machine::X86) if ptr64 => print_heuristic(
+            format!("{origin} C++ functions are __ptr64, which means they were compiled for a 64-bit target but the machine type is x86"),
+            args.quiet,
+        ),
+        _ if ptr64 => print_heuristic(
+            format!("{origin} C++ functions are __ptr64, which means they were compiled for a 64-bit target"),
+            args.quiet,
+        ),
+        _ => {}
+    }
+}
@@ -0,0 +1,149 @@
+use crate::compiler_version::CompilerVersion;
+use crate::info::Info;
+use crate::read::{read_rva, read_rva_cstring, read_u32, read_u64};
+use crate::{print_heuristic, print_self_reported, Args};
+use exe::{Arch, ImageDirectoryEntry, PtrPE, PE};
+
+const IMAGE_DELAYLOAD_DESCRIPTOR_SIZE: u32 = 32;
+/// Descriptors with this attribute have RVAs, the first format had virtual addresses instead.
+const DLATTR_RVA: u32 = 1;
+
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct DelayImport {
+    pub name: String,
+    /// Functions that are imported by name.
+    pub symbols: Vec<String>,
+    pub ordinals: Vec<u16>,
+    pub rva_based: bool,
+}
+
+/// Names in the first format are virtual addresses, which depend on the image base.
+fn to_rva(value: u64, rva_based: bool, image_base: u64) -> Option<u32> {
+    let value = if rva_based {
+        value
+    } else {
+        value.checked_sub(image_base)?
+    };
+    u32::try_from(value).ok()
+}
+
+/// Names and ordinals of the functions in an import name table.
+fn import_names(
+    pe: &PtrPE,
+    table: u32,
+    rva_based: bool,
+    image_base: u64,
+) -> (Vec<String>, Vec<u16>) {
+    let wide = matches!(pe.get_arch(), Ok(Arch::X64));
+    let (entry_size, ordinal_flag) = if wide { (8, 1 << 63) } else { (4, 1 << 31) };
+
+    let mut symbols = Vec::new();
+    let mut ordinals = Vec::new();
+    let mut offset = table;
+    while let Some(entry) = read_rva(pe, offset, entry_size).and_then(|a| {
+        if wide {
+            read_u64(a, 0)
+        } else {
+            read_u32(a, 0).map(u64::from)
+        }
+    }) {
+        if entry == 0 {
+            break;
+        }
+
+        // Names are after the two byte hint.
+        if entry & ordinal_flag != 0 {
+            ordinals.push(entry as u16);
+        } else if let Some(symbol) = to_rva(entry, rva_based, image_base)
+            .and_then(|a| a.checked_add(2))
+            .and_then(|a| read_rva_cstring(pe, a))
+        {
+            symbols.push(symbol);
+        }
+
+        let Some(next) = offset.checked_add(entry_size) else {
+            break;
+        };
+        offset = next;
+    }
+
+    (symbols, ordinals)
+}
+
+pub fn parse_delay_imports(pe: &PtrPE) -> Vec<DelayImport> {
+    let Ok(directory) = pe.get_data_directory(ImageDirectoryEntry::DelayImport) else {
+        return Vec::new();
+    };
+    if directory.virtual_address.0 == 0 {
+        return Vec::new();
+    }
+    let image_base = pe.get_image_base().unwrap_or_default();
+
+    let mut imports = Vec::new();
+    let mut offset = directory.virtual_address.0;
+    while let Some(descriptor) = read_rva(pe, offset, IMAGE_DELAYLOAD_DESCRIPTOR_SIZE) {
+        let (Some(attributes), Some(name), Some(names)) = (
+            read_u32(descriptor, 0),
+            read_u32(descriptor, 4),
+            read_u32(descriptor, 16),
+        ) else {
+            break;
+        };
+        // The list ends with a descriptor of zeroes.
+        if name == 0 {
+            break;
+        }
+
+        let rva_based = attributes & DLATTR_RVA != 0;
+        if let Some(name) =
+            to_rva(name.into(), rva_based, image_base).and_then(|a| read_rva_cstring(pe, a))
+        {
+            let (symbols, ordinals) = to_rva(names.into(), rva_based, image_base)
+                .map(|a| import_names(pe, a, rva_based, image_base))
+                .unwrap_or_default();
+            imports.push(DelayImport {
+                name,
+                symbols,
+                ordinals,
+                rva_based,
+            });
+        }
+
+        let Some(next) = offset.checked_add(IMAGE_DELAYLOAD_DESCRIPTOR_SIZE) else {
+            break;
+        };
+        offset = next;
+    }
+
+    imports
+}
+
+/// The descriptor format says which era of linker built the image.
+pub fn versions_from_delay_import_format(imports: &[DelayImport], info: &mut Info, args: &Args) {
+    if imports.is_empty() {
+        return;
+    }
+
+    let names = imports
+        .iter()
+        .map(|a| a.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    print_self_reported(format!("Image delay loads {names}"), args.quiet);
+
+    if imports.iter().all(|a| a.rva_based) {
+        print_heuristic(
+            format!(
+                "Delay load descriptors use RVAs, which the linker of {} and later write",
+                CompilerVersion::VisualStudioDotNet2002
+            ),
+            args.quiet,
+        );
+        info.exclude_compiler_versions_before(CompilerVersion::VisualStudioDotNet2002);
+    } else {
+        print_heuristic(
+            format!("Delay load descriptors use virtual addresses, which suggests a linker older than {} or a linker that is not from Microsoft", CompilerVersion::VisualStudioDotNet2002),
+            args.quiet,
+        );
+    }
+}
@@ -0,0 +1,831 @@
+//! Demangling of the names that the Microsoft compilers give to C++ symbols.
+
+/// A demangled symbol, the qualified name uses `::` between scopes like C++ source.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Symbol {
+    pub name: String,
+    pub access: Option<&'static str>,
+    pub storage: Option<&'static str>,
+    pub calling_convention: Option<&'static str>,
+    /// Return type of functions and type of variables.
+    pub kind: Option<String>,
+    /// Parameters of functions, `None` for variables.
+    pub parameters: Option<String>,
+    /// Qualifiers of `this` for member functions.
+    pub qualifiers: &'static str,
+    pub ptr64: bool,
+    /// C++/CLI functions that are compiled to MSIL are marked with `$$F` or `$$H`.
+    pub managed: Option<&'static str>,
+}
+
+impl Symbol {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            access: None,
+            storage: None,
+            calling_convention: None,
+            kind: None,
+            parameters: None,
+            qualifiers: "",
+            ptr64: false,
+            managed: None,
+        }
+    }
+}
+
+impl std::fmt::Display for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(access) = self.access {
+            write!(f, "{access}: ")?;
+        }
+        if let Some(storage) = self.storage {
+            write!(f, "{storage} ")?;
+        }
+        if let Some(kind) = &self.kind {
+            write!(f, "{kind} ")?;
+        }
+        if let Some(calling_convention) = self.calling_convention {
+            write!(f, "{calling_convention} ")?;
+        }
+        f.write_str(&self.name)?;
+        if let Some(parameters) = &self.parameters {
+            write!(f, "({parameters}){}", self.qualifiers)?;
+        }
+        if self.ptr64 {
+            f.write_str(" __ptr64")?;
+        }
+        if let Some(managed) = self.managed {
+            write!(f, " [{managed}]")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Special names that are written as `?` followed by a code instead of an identifier.
+enum Special {
+    Constructor,
+    Destructor,
+    /// Conversion operators are named after the type that they return.
+    Conversion,
+    Name(&'static str),
+    /// Run time type information, the digit says which structure.
+    Rtti(u8),
+    /// Functions generated to initialize or destroy a variable.
+    Dynamic(&'static str),
+}
+
+fn operator_name(code: u8) -> Option<&'static str> {
+    Some(match code {
+        b'2' => "operator new",
+        b'3' => "operator delete",
+        b'4' => "operator=",
+        b'5' => "operator>>",
+        b'6' => "operator<<",
+        b'7' => "operator!",
+        b'8' => "operator==",
+        b'9' => "operator!=",
+        b'A' => "operator[]",
+        b'C' => "operator->",
+        b'D' => "operator*",
+        b'E' => "operator++",
+        b'F' => "operator--",
+        b'G' => "operator-",
+        b'H' => "operator+",
+        b'I' => "operator&",
+        b'J' => "operator->*",
+        b'K' => "operator/",
+        b'L' => "operator%",
+        b'M' => "operator<",
+        b'N' => "operator<=",
+        b'O' => "operator>",
+        b'P' => "operator>=",
+        b'Q' => "operator,",
+        b'R' => "operator()",
+        b'S' => "operator~",
+        b'T' => "operator^",
+        b'U' => "operator|",
+        b'V' => "operator&&",
+        b'W' => "operator||",
+        b'X' => "operator*=",
+        b'Y' => "operator+=",
+        b'Z' => "operator-=",
+        _ => return None,
+    })
+}
+
+/// Names that start with `?_`, which are mostly functions and tables generated by the compiler.
+fn underscore_name(code: u8) -> Option<&'static str> {
+    Some(match code {
+        b'0' => "operator/=",
+        b'1' => "operator%=",
+        b'2' => "operator>>=",
+        b'3' => "operator<<=",
+        b'4' => "operator&=",
+        b'5' => "operator|=",
+        b'6' => "operator^=",
+        b'7' => "`vftable'",
+        b'8' => "`vbtable'",
+        b'9' => "`vcall'",
+        b'A' => "`typeof'",
+        b'B' => "`local static guard'",
+        b'D' => "`vbase destructor'",
+        b'E' => "`vector deleting destructor'",
+        b'F' => "`default constructor closure'",
+        b'G' => "`scalar deleting destructor'",
+        b'H' => "`vector constructor iterator'",
+        b'I' => "`vector destructor iterator'",
+        b'J' => "`vector vbase constructor iterator'",
+        b'K' => "`virtual displacement map'",
+        b'L' => "`eh vector constructor iterator'",
+        b'M' => "`eh vector destructor iterator'",
+        b'N' => "`eh vector vbase constructor iterator'",
+        b'O' => "`copy constructor closure'",
+        b'S' => "`local vftable'",
+        b'T' => "`local vftable constructor closure'",
+        b'U' => "operator new[]",
+        b'V' => "operator delete[]",
+        b'X' => "`placement delete closure'",
+        b'Y' => "`placement delete[] closure'",
+        _ => return None,
+    })
+}
+
+fn special_name(code: &[u8]) -> Option<(Special, usize)> {
+    Some(match code {
+        [b'0', ..] => (Special::Constructor, 1),
+        [b'1', ..] => (Special::Destructor, 1),
+        [b'B', ..] => (Special::Conversion, 1),
+        [b'_', b'_', b'E', ..] => (Special::Dynamic("dynamic initializer"), 3),
+        [b'_', b'_', b'F', ..] => (Special::Dynamic("dynamic atexit destructor"), 3),
+        [b'_', b'_', b'L', ..] => (Special::Name("operator co_await"), 3),
+        [b'_', b'_', b'M', ..] => (Special::Name("operator<=>"), 3),
+        [b'_', b'R', a @ b'0'..=b'4', ..] => (Special::Rtti(*a - b'0'), 3),
+        [b'_', a, ..] => (Special::Name(underscore_name(*a)?), 2),
+        [a, ..] => (Special::Name(operator_name(*a)?), 1),
+        _ => return None,
+    })
+}
+
+fn calling_convention(code: u8) -> Option<&'static str> {
+    Some(match code {
+        b'A' | b'B' => "__cdecl",
+        b'C' | b'D' => "__pascal",
+        b'E' | b'F' => "__thiscall",
+        b'G' | b'H' => "__stdcall",
+        b'I' | b'J' => "__fastcall",
+        b'M' | b'N' => "__clrcall",
+        b'Q' => "__vectorcall",
+        _ => return None,
+    })
+}
+
+fn primitive_type(code: u8) -> Option<&'static str> {
+    Some(match code {
+        b'C' => "signed char",
+        b'D' => "char",
+        b'E' => "unsigned char",
+        b'F' => "short",
+        b'G' => "unsigned short",
+        b'H' => "int",
+        b'I' => "unsigned int",
+        b'J' => "long",
+        b'K' => "unsigned long",
+        b'M' => "float",
+        b'N' => "double",
+        b'O' => "long double",
+        b'X' => "void",
+        _ => return None,
+    })
+}
+
+fn extended_type(code: u8) -> Option<&'static str> {
+    Some(match code {
+        b'D' => "__int8",
+        b'E' => "unsigned __int8",
+        b'F' => "__int16",
+        b'G' => "unsigned __int16",
+        b'H' => "__int32",
+        b'I' => "unsigned __int32",
+        b'J' => "__int64",
+        b'K' => "unsigned __int64",
+        b'L' => "__int128",
+        b'M' => "unsigned __int128",
+        b'N' => "bool",
+        b'Q' => "char8_t",
+        b'S' => "char16_t",
+        b'U' => "char32_t",
+        b'W' => "wchar_t",
+        _ => return None,
+    })
+}
+
+/// Qualifiers are written after the type they apply to, such as `char const *`.
+fn cv_qualifier(code: u8) -> Option<&'static str> {
+    Some(match code {
+        b'A' => "",
+        b'B' => " const",
+        b'C' => " volatile",
+        b'D' => " const volatile",
+        _ => return None,
+    })
+}
+
+/// Names are nested through templates, pointers and local scopes, deeper nesting is not a real name.
+const MAX_DEPTH: usize = 64;
+
+/// The compilers replace longer names with a hash, so longer input is not a real name.
+const MAX_LENGTH: usize = 4096;
+
+struct Parser<'a> {
+    input: &'a [u8],
+    position: usize,
+    /// How many types and name fragments are being parsed inside each other.
+    depth: usize,
+    /// Names that can be referred to with a digit.
+    names: Vec<String>,
+    /// Parameter types that can be referred to with a digit.
+    types: Vec<String>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a [u8]) -> Self {
+        Self {
+            input,
+            position: 0,
+            depth: 0,
+            names: Vec::new(),
+            types: Vec::new(),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let value = self.peek()?;
+        self.position += 1;
+        Some(value)
+    }
+
+    fn consume(&mut self, prefix: &[u8]) -> bool {
+        let matches = self.input[self.position..].starts_with(prefix);
+        if matches {
+            self.position += prefix.len();
+        }
+        matches
+    }
+
+    /// Parses something that can contain itself, giving up when it is nested too deeply.
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> Option<T>) -> Option<T> {
+        if self.depth >= MAX_DEPTH {
+            return None;
+        }
+
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn identifier(&mut self) -> Option<String> {
+        let rest = &self.input[self.position..];
+        let length = rest.iter().position(|a| *a == b'@')?;
+        self.position += length + 1;
+
+        Some(String::from_utf8_lossy(&rest[..length]).into_owned())
+    }
+
+    fn remember_name(&mut self, name: &str) {
+        if self.names.len() < 10 && !self.names.iter().any(|a| a == name) {
+            self.names.push(name.to_string());
+        }
+    }
+
+    /// Numbers are a digit for 1 to 10, or hexadecimal with the letters `A` to `P` ending with `@`.
+    fn number(&mut self) -> Option<i64> {
+        let negative = self.consume(b"?");
+        let value = match self.next()? {
+            a @ b'0'..=b'9' => (a - b'0') as i64 + 1,
+            b'@' => 0,
+            a @ b'A'..=b'P' => {
+                let mut value = (a - b'A') as i64;
+                loop {
+                    match self.next()? {
+                        b'@' => break,
+                        a @ b'A'..=b'P' => value = value * 16 + (a - b'A') as i64,
+                        _ => return None,
+                    }
+                }
+                value
+            }
+            _ => return None,
+        };
+
+        Some(if negative { -value } else { value })
+    }
+
+    fn template_name(&mut self) -> Option<String> {
+        // Template arguments have their own back references, which start with the template name.
+        let names = std::mem::take(&mut self.names);
+        let types = std::mem::take(&mut self.types);
+        let result = self.template_name_and_arguments();
+        self.names = names;
+        self.types = types;
+
+        result
+    }
+
+    fn template_name_and_arguments(&mut self) -> Option<String> {
+        let name = if self.consume(b"?") {
+            // Templated operators such as `operator<<` have a special name.
+            match special_name(&self.input[self.position..])? {
+                (Special::Name(name), length) => {
+                    self.position += length;
+                    name.to_string()
+                }
+                _ => return None,
+            }
+        } else {
+            let name = self.identifier()?;
+            self.remember_name(&name);
+            name
+        };
+
+        let mut arguments = Vec::new();
+        while !self.consume(b"@") {
+            if let Some(argument) = self.template_argument()? {
+                arguments.push(argument);
+            }
+        }
+
+        let arguments = arguments.join(",");
+        // Nested templates are separated so that `>>` is not read as an operator.
+        let space = if arguments.ends_with('>') { " " } else { "" };
+        Some(format!("{name}<{arguments}{space}>"))
+    }
+
+    /// A template argument, empty parameter packs have no argument.
+    fn template_argument(&mut self) -> Option<Option<String>> {
+        if self.consume(b"$$V") || self.consume(b"$$Z") || self.consume(b"$S") {
+            return Some(None);
+        }
+        if self.consume(b"$0") {
+            return self.number().map(|a| Some(a.to_string()));
+        }
+        if self.consume(b"$$C") {
+            let cv = cv_qualifier(self.next()?)?;
+            return self.parse_type().map(|a| Some(format!("{a}{cv}")));
+        }
+
+        self.argument_type().map(Some)
+    }
+
+    /// A single part of a qualified name.
+    fn name_fragment(&mut self) -> Option<String> {
+        self.nested(Self::unnested_name_fragment)
+    }
+
+    fn unnested_name_fragment(&mut self) -> Option<String> {
+        match self.peek()? {
+            a @ b'0'..=b'9' => {
+                self.position += 1;
+                self.names.get((a - b'0') as usize).cloned()
+            }
+            b'?' if self.consume(b"?$") => {
+                let name = self.template_name()?;
+                self.remember_name(&name);
+                Some(name)
+            }
+            b'?' if self.consume(b"?A") => {
+                // Anonymous namespaces have a generated name such as `?A0x1234abcd`.
+                self.identifier()?;
+                Some("`anonymous namespace'".to_string())
+            }
+            b'?' => {
+                // Scopes inside functions are numbered, and local static variables name the function.
+                self.position += 1;
+                let number = self.number()?;
+                if !self.consume(b"??") {
+                    return Some(format!("`{number}'"));
+                }
+                let function = self.symbol()?;
+                Some(format!("`{function}'::`{number}'"))
+            }
+            _ => {
+                let name = self.identifier()?;
+                self.remember_name(&name);
+                Some(name)
+            }
+        }
+    }
+
+    /// Scopes from innermost to outermost ending with `@`, returned outermost first.
+    fn scopes(&mut self) -> Option<Vec<String>> {
+        let mut scopes = Vec::new();
+        while !self.consume(b"@") {
+            scopes.push(self.name_fragment()?);
+        }
+        scopes.reverse();
+
+        Some(scopes)
+    }
+
+    fn qualified_name(&mut self) -> Option<String> {
+        let name = self.name_fragment()?;
+        let mut parts = self.scopes()?;
+        parts.push(name);
+
+        Some(parts.join("::"))
+    }
+
+    /// Pointers and references, which have qualifiers for the pointer and for the pointee.
+    fn pointer(&mut self, operator: &str, cv: &str) -> Option<String> {
+        // Pointers to functions put the declarator in parentheses, such as `int (__cdecl *)(int)`.
+        if self.consume(b"6") {
+            let calling_convention = calling_convention(self.next()?)?;
+            let (return_type, parameters) = self.function_type()?;
+            return Some(format!(
+                "{return_type} ({calling_convention} {operator}{cv})({parameters})"
+            ));
+        }
+        if self.consume(b"8") {
+            let class = self.qualified_name()?;
+            self.consume(b"E");
+            let qualifiers = cv_qualifier(self.next()?)?;
+            let calling_convention = calling_convention(self.next()?)?;
+            let (return_type, parameters) = self.function_type()?;
+            return Some(format!(
+                "{return_type} ({calling_convention} {class}::{operator}{cv})({parameters}){qualifiers}"
+            ));
+        }
+
+        let mut ptr64 = "";
+        loop {
+            if self.consume(b"E") {
+                ptr64 = " __ptr64";
+            } else if !(self.consume(b"I") || self.consume(b"F")) {
+                break;
+            }
+        }
+
+        let (pointee_cv, class) = match self.next()? {
+            a @ b'A'..=b'D' => (cv_qualifier(a)?, String::new()),
+            // Pointers to data members name the class.
+            a @ b'Q'..=b'T' => (
+                cv_qualifier(a - b'Q' + b'A')?,
+                format!("{}::", self.qualified_name()?),
+            ),
+            _ => return None,
+        };
+
+        if self.consume(b"Y") {
+            let mut dimensions = String::new();
+            for _ in 0..self.number()? {
+                dimensions += &format!("[{}]", self.number()?);
+            }
+            let element = self.parse_type()?;
+            return Some(format!(
+                "{element}{pointee_cv} ({class}{operator}{cv}{ptr64}){dimensions}"
+            ));
+        }
+
+        // Pointers have their own qualifiers, which are repeated as the qualifiers of the pointee.
+        let pointee_cv = match self.peek()? {
+            b'P'..=b'S' => "",
+            _ => pointee_cv,
+        };
+        let pointee = self.parse_type()?;
+        // Pointers to pointers are written together, such as `int *&`.
+        let space = if pointee.ends_with(['*', '&']) && pointee_cv.is_empty() {
+            ""
+        } else {
+            " "
+        };
+        Some(format!(
+            "{pointee}{pointee_cv}{space}{class}{operator}{cv}{ptr64}"
+        ))
+    }
+
+    fn parse_type(&mut self) -> Option<String> {
+        self.nested(Self::unnested_type)
+    }
+
+    fn unnested_type(&mut self) -> Option<String> {
+        let code = self.next()?;
+        Some(match code {
+            b'_' => extended_type(self.next()?)?.to_string(),
+            b'V' => format!("class {}", self.qualified_name()?),
+            b'U' => format!("struct {}", self.qualified_name()?),
+            b'T' => format!("union {}", self.qualified_name()?),
+            b'W' => {
+                self.next()?;
+                format!("enum {}", self.qualified_name()?)
+            }
+            b'$' if self.consume(b"$Q") => self.pointer("&&", "")?,
+            b'$' if self.consume(b"$R") => self.pointer("&&", " volatile")?,
+            b'$' if self.consume(b"$T") => "std::nullptr_t".to_string(),
+            b'A' => self.pointer("&", "")?,
+            b'B' => self.pointer("&", " volatile")?,
+            b'P' => self.pointer("*", "")?,
+            b'Q' => self.pointer("*", " const")?,
+            b'R' => self.pointer("*", " volatile")?,
+            b'S' => self.pointer("*", " const volatile")?,
+            _ => primitive_type(code)?.to_string(),
+        })
+    }
+
+    /// Types of parameters and template arguments, which can be referred to later with a digit.
+    fn argument_type(&mut self) -> Option<String> {
+        if let Some(a @ b'0'..=b'9') = self.peek() {
+            self.position += 1;
+            return self.types.get((a - b'0') as usize).cloned();
+        }
+
+        let start = self.position;
+        let value = self.parse_type()?;
+        // Only types that are longer than a single letter are remembered.
+        if self.position - start > 1 && self.types.len() < 10 {
+            self.types.push(value.clone());
+        }
+
+        Some(value)
+    }
+
+    /// Return types can have qualifiers, and are left out for constructors and destructors.
+    fn return_type(&mut self) -> Option<Option<String>> {
+        if self.consume(b"@") {
+            return Some(None);
+        }
+        if self.consume(b"?") {
+            let cv = cv_qualifier(self.next()?)?;
+            return self.parse_type().map(|a| Some(format!("{a}{cv}")));
+        }
+
+        self.parse_type().map(Some)
+    }
+
+    fn parameters(&mut self) -> Option<String> {
+        if self.consume(b"X") {
+            return Some("void".to_string());
+        }
+
+        let mut parameters = Vec::new();
+        loop {
+            if self.consume(b"@") {
+                break;
+            }
+            // Variadic functions end the list with `Z` instead of `@`.
+            if self.consume(b"Z") {
+                parameters.push("...".to_string());
+                break;
+            }
+            parameters.push(self.argument_type()?);
+        }
+
+        Some(parameters.join(", "))
+    }
+
+    /// Return type and parameters, followed by an exception specification that is always `Z`.
+    fn function_type(&mut self) -> Option<(String, String)> {
+        let return_type = self.return_type()?.unwrap_or_default();
+        let parameters = self.parameters()?;
+        if !self.consume(b"Z") {
+            return None;
+        }
+
+        Some((return_type, parameters))
+    }
+
+    /// The special name at the start of a symbol, if there is one.
+    fn special(&mut self) -> Option<Option<Special>> {
+        if self.peek()? != b'?' || self.input.get(self.position + 1) == Some(&b'$') {
+            return Some(None);
+        }
+
+        let (special, length) = special_name(&self.input[self.position + 1..])?;
+        self.position += 1 + length;
+        Some(Some(special))
+    }
+
+    /// The qualified name of a symbol that starts with `special`.
+    fn special_symbol_name(&mut self, special: Special) -> Option<String> {
+        let unqualified = match special {
+            Special::Dynamic(kind) => {
+                let variable = self.qualified_name()?;
+                return Some(format!("`{kind} for '{variable}''"));
+            }
+            // Base class descriptors have the position of the base class in the derived class.
+            Special::Rtti(1) => {
+                let mut numbers = Vec::new();
+                for _ in 0..4 {
+                    numbers.push(self.number()?.to_string());
+                }
+                format!("`RTTI Base Class Descriptor at ({})'", numbers.join(", "))
+            }
+            Special::Rtti(2) => "`RTTI Base Class Array'".to_string(),
+            Special::Rtti(3) => "`RTTI Class Hierarchy Descriptor'".to_string(),
+            Special::Rtti(_) => "`RTTI Complete Object Locator'".to_string(),
+            Special::Name(name) => name.to_string(),
+            Special::Conversion => "operator".to_string(),
+            Special::Constructor | Special::Destructor => String::new(),
+        };
+
+        let mut parts = self.scopes()?;
+        // Constructors and destructors of templates do not repeat the template arguments.
+        let class = parts.last().cloned().unwrap_or_default();
+        let class = class.split('<').next().unwrap_or_default();
+        parts.push(match special {
+            Special::Constructor => class.to_string(),
+            Special::Destructor => format!("~{class}"),
+            _ => unqualified,
+        });
+
+        Some(parts.join("::"))
+    }
+
+    fn symbol(&mut self) -> Option<Symbol> {
+        let special = self.special()?;
+        let conversion = matches!(special, Some(Special::Conversion));
+        let name = match special {
+            // Type descriptors are for any type, not only classes.
+            Some(Special::Rtti(0)) => {
+                let kind = self.return_type()??;
+                return Some(Symbol::new(format!("{kind} `RTTI Type Descriptor'")));
+            }
+            Some(special) => self.special_symbol_name(special)?,
+            None => self.qualified_name()?,
+        };
+
+        let mut symbol = Symbol::new(name);
+        // C++/CLI marks functions that are compiled to MSIL.
+        if self.consume(b"$$F") {
+            symbol.managed = Some("managed");
+        } else if self.consume(b"$$H") {
+            symbol.managed = Some("managed with native entry point");
+        }
+
+        let code = self.next()?;
+        match code {
+            // Run time type information other than the type descriptor.
+            b'8' => return Some(symbol),
+            // Virtual tables can say which base class they are for.
+            b'6' | b'7' => {
+                symbol.kind = Some(cv_qualifier(self.next()?)?.trim_start().to_string());
+                let mut bases = Vec::new();
+                while !self.consume(b"@") {
+                    bases.push(format!("`{}'", self.qualified_name()?));
+                }
+                if !bases.is_empty() {
+                    symbol.name += &format!("{{for {}}}", bases.join("s "));
+                }
+                return Some(symbol);
+            }
+            // Variables and static members.
+            b'0'..=b'4' => {
+                symbol.access = match code {
+                    b'0' => Some("private"),
+                    b'1' => Some("protected"),
+                    b'2' => Some("public"),
+                    _ => None,
+                };
+                symbol.storage = symbol.access.map(|_| "static");
+                symbol.kind = Some(self.parse_type()?);
+                return Some(symbol);
+            }
+            b'A'..=b'X' => {
+                symbol.access = Some(match (code - b'A') / 8 {
+                    0 => "private",
+                    1 => "protected",
+                    _ => "public",
+                });
+                symbol.storage = match (code - b'A') % 8 / 2 {
+                    1 => Some("static"),
+                    2 => Some("virtual"),
+                    3 => Some("thunk"),
+                    _ => None,
+                };
+            }
+            b'Y' | b'Z' => {}
+            _ => return None,
+        }
+
+        // Thunks adjust `this` by a number of bytes first.
+        if symbol.storage == Some("thunk") {
+            self.number()?;
+        }
+
+        // Member functions that are not static have the qualifiers of `this`.
+        if symbol.access.is_some() && symbol.storage != Some("static") {
+            symbol.ptr64 = self.consume(b"E");
+            symbol.qualifiers = cv_qualifier(self.next()?)?;
+        }
+        symbol.calling_convention = Some(calling_convention(self.next()?)?);
+
+        let (return_type, parameters) = self.function_type()?;
+        if conversion {
+            symbol.name += &format!(" {return_type}");
+        } else if !return_type.is_empty() {
+            symbol.kind = Some(return_type);
+        }
+        symbol.parameters = Some(parameters);
+
+        Some(symbol)
+    }
+}
+
+/// Demangles a symbol decorated by the Microsoft compilers, such as `?size@?$vector@HV?$allocator@H@std@@@std@@QBEIXZ`.
+pub fn demangle(mangled: &str) -> Option<Symbol> {
+    // String literals only have the length and a hash of the contents.
+    if mangled.starts_with("??_C@_") {
+        return Some(Symbol::new("`string'".to_string()));
+    }
+
+    if mangled.len() > MAX_LENGTH {
+        return None;
+    }
+
+    Parser::new(mangled.strip_prefix('?')?.as_bytes()).symbol()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::demangle;
+
+    /// Names and what `undname` makes of them.
+    const SYMBOLS: &[(&str, &str)] = &[
+        ("?func@@YAHH@Z", "int __cdecl func(int)"),
+        ("?x@@3HA", "int x"),
+        ("?n@Foo@@2HA", "public: static int Foo::n"),
+        ("?m@@3PEBDEB", "char const * __ptr64 m"),
+        ("??1Foo@@UAE@XZ", "public: virtual __thiscall Foo::~Foo(void)"),
+        ("??2@YAPAXI@Z", "void * __cdecl operator new(unsigned int)"),
+        (
+            "??4Foo@@QAEAAV0@ABV0@@Z",
+            "public: class Foo & __thiscall Foo::operator=(class Foo const &)",
+        ),
+        ("??BFoo@@QAEHXZ", "public: __thiscall Foo::operator int(void)"),
+        (
+            "??0exception@std@@QAE@ABQBD@Z",
+            "public: __thiscall std::exception::exception(char const * const &)",
+        ),
+        (
+            "??$max@H@std@@YAABHABH0@Z",
+            "int const & __cdecl std::max<int>(int const &, int const &)",
+        ),
+        (
+            "?size@?$vector@HV?$allocator@H@std@@@std@@QBEIXZ",
+            "public: unsigned int __thiscall std::vector<int,class std::allocator<int> >::size(void) const",
+        ),
+        (
+            "?bar@Baz@ns@@SGXPAD@Z",
+            "public: static void __stdcall ns::Baz::bar(char *)",
+        ),
+        ("?f@@YAXP6AHH@Z@Z", "void __cdecl f(int (__cdecl *)(int))"),
+        ("?g@@YAXPAY02H@Z", "void __cdecl g(int (*)[3])"),
+        (
+            "?o@@YAXP8Foo@@AEXXZ@Z",
+            "void __cdecl o(void (__thiscall Foo::*)(void))",
+        ),
+        ("?p@@YAXPQFoo@@H@Z", "void __cdecl p(int Foo::*)"),
+        ("?s@@YAXAAPAH@Z", "void __cdecl s(int *&)"),
+        ("?h@@YAXZZ", "void __cdecl h(...)"),
+        ("?f@?A0x1234abcd@@YAXXZ", "void __cdecl `anonymous namespace'::f(void)"),
+        ("?x@?1??f@@YAXXZ@4HA", "int `void __cdecl f(void)'::`2'::x"),
+        ("??_7Foo@@6BBar@@@", "const Foo::`vftable'{for `Bar'}"),
+        ("??_R0?AVFoo@@@8", "class Foo `RTTI Type Descriptor'"),
+        (
+            "??_R1A@?0A@EA@Foo@@8",
+            "Foo::`RTTI Base Class Descriptor at (0, -1, 0, 64)'",
+        ),
+        (
+            "??__Efoo@@YAXXZ",
+            "void __cdecl `dynamic initializer for 'foo''(void)",
+        ),
+        ("??_C@_05CJBACGMB@hello?$AA@", "`string'"),
+    ];
+
+    #[test]
+    fn matches_undname() {
+        for (mangled, expected) in SYMBOLS {
+            let demangled = demangle(mangled).map(|a| a.to_string());
+            assert_eq!(demangled.as_deref(), Some(*expected), "{mangled}");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_names() {
+        assert_eq!(demangle("func"), None);
+        assert_eq!(demangle("?i@@YAXHZ"), None);
+    }
+
+    #[test]
+    fn rejects_deep_nesting() {
+        let pointers = "PA".repeat(100);
+        assert_eq!(demangle(&format!("?f@@YAX{pointers}H@Z")), None);
+        let pointers = "PA".repeat(10_000);
+        assert_eq!(demangle(&format!("?x@@3{pointers}HA")), None);
+    }
+}
@@ -0,0 +1,152 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+
+pub const INTEGER: u8 = 0x02;
+pub const OCTET_STRING: u8 = 0x04;
+pub const OBJECT_IDENTIFIER: u8 = 0x06;
+pub const UTF8_STRING: u8 = 0x0C;
+pub const PRINTABLE_STRING: u8 = 0x13;
+pub const T61_STRING: u8 = 0x14;
+pub const IA5_STRING: u8 = 0x16;
+pub const UTC_TIME: u8 = 0x17;
+pub const GENERALIZED_TIME: u8 = 0x18;
+pub const BMP_STRING: u8 = 0x1E;
+pub const SEQUENCE: u8 = 0x30;
+pub const SET: u8 = 0x31;
+pub const CONTEXT_0: u8 = 0xA0;
+pub const CONTEXT_1: u8 = 0xA1;
+
+/// A single DER element, only definite lengths are supported.
+#[derive(Debug, Copy, Clone)]
+pub struct Element<'a> {
+    pub tag: u8,
+    pub contents: &'a [u8],
+    /// The complete encoding including tag and length, used for comparing elements.
+    pub raw: &'a [u8],
+}
+
+impl<'a> Element<'a> {
+    /// Reads the element at the start of `data`, returning it and the remaining data.
+    pub fn parse(data: &'a [u8]) -> Option<(Self, &'a [u8])> {
+        let tag = *data.first()?;
+        let first = *data.get(1)? as usize;
+
+        let (length, header) = if first & 0x80 == 0 {
+            (first, 2)
+        } else {
+            let amount = first & 0x7F;
+            if amount == 0 || amount > 4 {
+                return None;
+            }
+
+            let length = data
+                .get(2..2 + amount)?
+                .iter()
+                .fold(0_usize, |a, b| (a << 8) | *b as usize);
+            (length, 2 + amount)
+        };
+
+        let end = header.checked_add(length)?;
+        let element = Self {
+            tag,
+            contents: data.get(header..end)?,
+            raw: data.get(..end)?,
+        };
+
+        Some((element, &data[end..]))
+    }
+
+    /// Elements inside a constructed element, stopping at the first one that can not be read.
+    pub fn children(&self) -> Vec<Element<'a>> {
+        let mut children = Vec::new();
+
+        let mut rest = self.contents;
+        while let Some((child, remaining)) = Self::parse(rest) {
+            children.push(child);
+            rest = remaining;
+        }
+
+        children
+    }
+
+    pub fn child(&self, index: usize) -> Option<Element<'a>> {
+        self.children().get(index).copied()
+    }
+
+    pub fn oid(&self) -> Option<String> {
+        if self.tag != OBJECT_IDENTIFIER || self.contents.is_empty() {
+            return None;
+        }
+
+        let mut components = Vec::new();
+        let mut value = 0_u64;
+        for byte in self.contents {
+            value = (value << 7) | (*byte & 0x7F) as u64;
+            if byte & 0x80 != 0 {
+                continue;
+            }
+
+            // The first two components share the first subidentifier.
+            if components.is_empty() {
+                let first = (value / 40).min(2);
+                components.push(first);
+                components.push(value - first * 40);
+            } else {
+                components.push(value);
+            }
+            value = 0;
+        }
+
+        Some(
+            components
+                .iter()
+                .map(|a| a.to_string())
+                .collect::<Vec<_>>()
+                .join("."),
+        )
+    }
+
+    pub fn string(&self) -> Option<String> {
+        match self.tag {
+            UTF8_STRING | PRINTABLE_STRING | T61_STRING | IA5_STRING => {
+                Some(String::from_utf8_lossy(self.contents).into_owned())
+            }
+            BMP_STRING => {
+                let characters = self
+                    .contents
+                    .chunks_exact(2)
+                    .map(|a| u16::from_be_bytes([a[0], a[1]]))
+                    .collect::<Vec<_>>();
+                Some(String::from_utf16_lossy(&characters))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn time(&self) -> Option<DateTime<Utc>> {
+        let text = std::str::from_utf8(self.contents).ok()?;
+        let text = text.strip_suffix('Z')?;
+        // Fractions of seconds are allowed in GeneralizedTime.
+        let text = text.split('.').next()?;
+
+        let (year, rest) = match self.tag {
+            UTC_TIME => {
+                let year = text.get(..2)?.parse::<i32>().ok()?;
+                (
+                    if year >= 50 { 1900 + year } else { 2000 + year },
+                    &text[2..],
+                )
+            }
+            GENERALIZED_TIME => (text.get(..4)?.parse::<i32>().ok()?, &text[4..]),
+            _ => return None,
+        };
+
+        let number = |index: usize| rest.get(index..index + 2)?.parse::<u32>().ok();
+        let date = NaiveDate::from_ymd_opt(year, number(0)?, number(2)?)?;
+        let time = NaiveTime::from_hms_opt(number(4)?, number(6)?, number(8).unwrap_or(0))?;
+
+        Some(DateTime::from_naive_utc_and_offset(
+            NaiveDateTime::new(date, time),
+            Utc,
+        ))
+    }
+}
@@ -1,17 +1,96 @@
 use crate::compiler_version::CompilerVersion;
+use crate::cpp_symbols::versions_from_cpp_symbols;
+use crate::delay_imports::{parse_delay_imports, versions_from_delay_import_format};
+use crate::demangle::demangle;
 use crate::info::Info;
+use crate::intel::versions_from_intel_runtimes;
+use crate::likely_incorrect::LikelyIncorrect;
+use crate::ordinals::{ordinal_introduced, ordinal_name};
 use crate::standard::get_cpp_standard_for_function;
-use crate::{print_heuristic, Args};
-use exe::{CCharString, ImageImportDescriptor, ImportData, ImportDirectory, PtrPE};
+use crate::{print_heuristic, print_self_reported, Args};
+use exe::{
+    CCharString, ImageDirectoryEntry, ImageImportDescriptor, ImportData, ImportDirectory, PtrPE, PE,
+};
+
+/// A DLL and the functions that are imported from it.
+struct Module {
+    name: String,
+    symbols: Vec<String>,
+    ordinals: Vec<u16>,
+    delay_loaded: bool,
+}
+
+impl Module {
+    fn links(&self) -> &'static str {
+        if self.delay_loaded {
+            "Delay loads"
+        } else {
+            "Links against"
+        }
+    }
+
+    fn origin(&self) -> &'static str {
+        if self.delay_loaded {
+            "Delay loaded"
+        } else {
+            "Imported"
+        }
+    }
+}
 
 pub fn versions_from_imported_dlls(pe: PtrPE, info: &mut Info, args: &Args) -> Result<(), String> {
-    let Ok(import_directory) = ImportDirectory::parse(&pe) else {
-        info.set_likely_incorrect();
-        return Err("Unable to parse import directory".to_string());
-    };
+    let mut modules = Vec::new();
+
+    // EFI images and some drivers do not import anything.
+    let has_imports = pe
+        .get_data_directory(ImageDirectoryEntry::Import)
+        .is_ok_and(|a| a.virtual_address.0 != 0 && a.size != 0);
+    if has_imports {
+        let Ok(import_directory) = ImportDirectory::parse(&pe) else {
+            info.set_likely_incorrect(LikelyIncorrect::Malformed);
+            return Err("Unable to parse import directory".to_string());
+        };
+
+        for descriptor in import_directory.descriptors {
+            let Some((lib, imports)) = get_name_and_imports(descriptor, &pe) else {
+                continue;
+            };
+
+            let mut symbols = Vec::new();
+            let mut ordinals = Vec::new();
+            for import in imports {
+                match import {
+                    ImportData::ImportByName(name) => symbols.push(name.to_string()),
+                    ImportData::Ordinal(ordinal) => ordinals.push(ordinal as u16),
+                }
+            }
+            modules.push(Module {
+                name: lib.to_string(),
+                symbols,
+                ordinals,
+                delay_loaded: false,
+            });
+        }
+    } else {
+        print_self_reported("Image has no import directory", args.quiet);
+    }
 
-    specific_imports(&import_directory, &pe, info, args);
-    specific_symbols(&import_directory, &pe, info, args);
+    // Delay loaded DLLs are not in the import directory, but are as much a sign of the toolchain.
+    let delay_imports = parse_delay_imports(&pe);
+    versions_from_delay_import_format(&delay_imports, info, args);
+    modules.extend(delay_imports.into_iter().map(|a| Module {
+        name: a.name,
+        symbols: a.symbols,
+        ordinals: a.ordinals,
+        delay_loaded: true,
+    }));
+
+    resolve_ordinals(&mut modules, info, args);
+    specific_imports(&modules, info, args);
+    specific_symbols(&modules, info, args);
+
+    let modules = modules.into_iter().map(|a| a.name).collect::<Vec<_>>();
+    versions_from_intel_runtimes(&modules, info, args);
 
     Ok(())
 }
@@ -21,11 +100,7 @@ fn get_name_and_imports<'a>(
     pe: &'a PtrPE,
 ) -> Option<(&'a str, Vec<ImportData<'a>>)> {
     let lib = if let Ok(lib) = descriptor.get_name(pe) {
-        if let Ok(lib) = lib.as_str() {
-            lib
-        } else {
-            ""
-        }
+        lib.as_str().unwrap_or_default()
     } else {
         ""
     };
@@ -37,92 +112,140 @@ fn get_name_and_imports<'a>(
     Some((lib, imports))
 }
 
-fn specific_symbols(import_directory: &ImportDirectory, pe: &PtrPE, info: &mut Info, args: &Args) {
-    for descriptor in import_directory.descriptors {
-        let Some((lib, imports)) = get_name_and_imports(descriptor, pe) else {
+/// Imports by ordinal are named where the DLL has fixed ordinals, so that they are matched like other symbols.
+fn resolve_ordinals(modules: &mut [Module], info: &mut Info, args: &Args) {
+    for module in modules {
+        if module.ordinals.is_empty() {
             continue;
-        };
+        }
 
-        let mut cpp_symbol = imports.iter().filter_map(|a| match a {
-            ImportData::ImportByName(name) => {
-                if name.starts_with("?") {
-                    Some(name)
-                } else {
-                    None
-                }
-            }
-            _ => None,
-        });
+        let names = module
+            .ordinals
+            .iter()
+            .filter_map(|a| ordinal_name(&module.name, *a))
+            .collect::<Vec<_>>();
+        // MFC members are decorated, so they are shown demangled.
+        let first = names
+            .first()
+            .map(|a| demangle(a).map_or(a.to_string(), |a| a.to_string()));
+        match first {
+            Some(first) => print_self_reported(
+                format!(
+                    "{} of the {} imports by ordinal from {} are known, such as {first}",
+                    names.len(),
+                    module.ordinals.len(),
+                    module.name
+                ),
+                args.quiet,
+            ),
+            None => print_self_reported(
+                format!(
+                    "{} imports from {} are by ordinal only",
+                    module.ordinals.len(),
+                    module.name
+                ),
+                args.quiet,
+            ),
+        }
+        module
+            .symbols
+            .extend(names.into_iter().map(|a| a.to_string()));
 
-        while let Some(symbol) = cpp_symbol.next() {
-            info.set_cpp(true);
+        // Ordinals that were added to a DLL later need a newer version of it.
+        let newest = module
+            .ordinals
+            .iter()
+            .filter_map(|a| Some((*a, ordinal_introduced(&module.name, *a)?)))
+            .max_by_key(|(_, version)| *version);
+        if let Some((ordinal, version)) = newest {
             print_heuristic(
-                format!("Imported symbol '{symbol}' from '{lib}' suggests C++ was used."),
+                format!(
+                    "Import by ordinal {ordinal} from {} suggests at least {version}",
+                    module.name
+                ),
                 args.quiet,
             );
-
-            break;
+            info.exclude_compiler_versions_before(version);
         }
     }
+}
 
-    for descriptor in import_directory.descriptors {
-        let Some((lib, imports)) = get_name_and_imports(descriptor, pe) else {
-            continue;
-        };
+fn specific_symbols(modules: &[Module], info: &mut Info, args: &Args) {
+    let mut symbols = Vec::new();
+    for module in modules {
+        let cpp_symbols = module
+            .symbols
+            .iter()
+            .filter(|a| a.starts_with('?'))
+            .collect::<Vec<_>>();
 
-        for import in imports {
-            match import {
-                ImportData::Ordinal(_) => {}
-                ImportData::ImportByName(name) => {
-                    if let Some(standard) = get_cpp_standard_for_function(name) {
-                        if info.is_cpp() {
-                            print_heuristic( format!("Import of symbol '{name}' from '{lib}' suggests at least standard {standard}"), args.quiet);
-                            info.add_standard(standard);
-                        }
-                    }
+        if let Some(symbol) = cpp_symbols.first() {
+            info.set_cpp(true);
+            // Symbols that cannot be demangled are shown as they are.
+            let readable = demangle(symbol).map_or(symbol.to_string(), |a| a.to_string());
+            print_heuristic(
+                format!(
+                    "{} symbol '{readable}' from '{}' suggests C++ was used.",
+                    module.origin(),
+                    module.name
+                ),
+                args.quiet,
+            );
+        }
+        symbols.extend(cpp_symbols.into_iter().filter_map(|a| demangle(a)));
+    }
+    versions_from_cpp_symbols(&symbols, "Imported", info, args);
+
+    for module in modules {
+        for name in &module.symbols {
+            if let Some(standard) = get_cpp_standard_for_function(name) {
+                if info.is_cpp() {
+                    print_heuristic(
+                        format!(
+                            "Import of symbol '{name}' from '{}' suggests at least standard {standard}",
+                            module.name
+                        ),
+                        args.quiet,
+                    );
+                    info.add_standard(standard);
                 }
             }
         }
     }
 }
 
-fn specific_imports(import_directory: &ImportDirectory, pe: &PtrPE, info: &mut Info, args: &Args) {
-    for descriptor in import_directory.descriptors {
-        let Ok(module_name) = descriptor.get_name(pe) else {
-            continue;
-        };
-
-        let Ok(module_name) = module_name.as_str() else {
-            continue;
-        };
+fn specific_imports(modules: &[Module], info: &mut Info, args: &Args) {
+    for module in modules {
+        let module_name = &module.name;
+        let links = module.links();
 
         let one = |cv: CompilerVersion, info: &mut Info| {
-            let time_validity = if info.timestamp() > cv.release_date() {
+            let time_validity = if info.possible_at_timestamp(cv.release_date()) {
                 "is possible"
             } else {
-                info.set_likely_incorrect();
+                info.set_likely_incorrect(LikelyIncorrect::ReleasedAfterTimestamp);
                 "isn't possible"
             };
 
             print_heuristic(format!(
-                "Links against {module_name} which suggests compiler is {}, which {time_validity} with the release date of the compiler",
+                "{links} {module_name} which suggests compiler is {}, which {time_validity} with the release date of the compiler",
                 cv,
             ), args.quiet);
             info.exclude_all_compiler_versions_except(cv)
         };
 
         let multi = |cvs: &[CompilerVersion], info: &mut Info| {
-            let mut s = format!("Links against {module_name} which suggests compiler is either ",);
+            let mut s = format!("{links} {module_name} which suggests compiler is either ");
 
             for (i, cv) in cvs.iter().enumerate() {
                 if i != 0 {
                     s += ", "
                 }
 
-                let time_validity = if info.timestamp() > cv.release_date() {
+                let time_validity = if info.possible_at_timestamp(cv.release_date()) {
                     "valid for timestamp"
                 } else {
-                    info.set_likely_incorrect();
+                    info.set_likely_incorrect(LikelyIncorrect::ReleasedAfterTimestamp);
                     "not valid for timestamp"
                 };
                 s += &format!("{cv} ({time_validity})");
@@ -161,9 +284,37 @@ fn specific_imports(import_directory: &ImportDirectory, pe: &PtrPE, info: &mut I
                 ],
                 info,
             ),
+            "coredll.dll" => print_heuristic(
+                format!("{links} {module_name} which suggests a Windows CE executable"),
+                args.quiet,
+            ),
             module => {
-                if module.contains("api-ms-win-crt") {
-                    multi(
+                // MFC and ATL have Unicode and debug builds such as mfc140ud.dll.
+                let runtime = module
+                    .strip_suffix(".dll")
+                    .unwrap_or(module)
+                    .trim_end_matches(['u', 'd']);
+                match runtime {
+                    "mfc40" => multi(
+                        &[CompilerVersion::VisualCPP4_0, CompilerVersion::VisualCPP4_1],
+                        info,
+                    ),
+                    "mfc42" => multi(
+                        &[
+                            CompilerVersion::VisualCPP4_2,
+                            CompilerVersion::VisualCPP5_0,
+                            CompilerVersion::VisualCPP6_0,
+                        ],
+                        info,
+                    ),
+                    "mfc70" => one(CompilerVersion::VisualStudioDotNet2002, info),
+                    "mfc71" | "atl71" => one(CompilerVersion::VisualStudioDotNet2003, info),
+                    "mfc80" | "atl80" => one(CompilerVersion::VisualStudio2005, info),
+                    "mfc90" | "atl90" => one(CompilerVersion::VisualStudio2008, info),
+                    "mfc100" | "atl100" => one(CompilerVersion::VisualStudio2010, info),
+                    "mfc110" | "atl110" => one(CompilerVersion::VisualStudio2012, info),
+                    "mfc120" => one(CompilerVersion::VisualStudio2013, info),
+                    "mfc140" => multi(
                         &[
                             CompilerVersion::VisualStudio2015,
                             CompilerVersion::VisualStudio2017,
@@ -171,7 +322,17 @@ fn specific_imports(import_directory: &ImportDirectory, pe: &PtrPE, info: &mut I
                             CompilerVersion::VisualStudio2022,
                         ],
                         info,
-                    );
+                    ),
+                    _ if module.contains("api-ms-win-crt") => multi(
+                        &[
+                            CompilerVersion::VisualStudio2015,
+                            CompilerVersion::VisualStudio2017,
+                            CompilerVersion::VisualStudio2019,
+                            CompilerVersion::VisualStudio2022,
+                        ],
+                        info,
+                    ),
+                    _ => {}
                 }
             }
         }
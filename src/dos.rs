@@ -0,0 +1,286 @@
+use crate::compiler_version::CompilerVersion;
+use crate::info::Info;
+use crate::likely_incorrect::LikelyIncorrect;
+use crate::read::{find_bytes, read_u16, read_u32};
+use crate::toolchain::Toolchain;
+use crate::versions_for_operating_system::OperatingSystem;
+use crate::{print_heuristic, print_self_reported, Args};
+
+const PE_SIGNATURE: &[u8] = b"PE\0\0";
+
+/// Amount of bytes after the entry point that are searched for startup code and banners.
+const ENTRY_WINDOW: usize = 0x100;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Compiler {
+    Microsoft,
+    TurboC,
+    BorlandCpp,
+    TurboPascal,
+    Watcom,
+    Djgpp,
+}
+
+impl Compiler {
+    fn name(&self) -> &'static str {
+        match self {
+            Compiler::Microsoft => "Microsoft C",
+            Compiler::TurboC => "Turbo C",
+            Compiler::BorlandCpp => "Borland C++",
+            Compiler::TurboPascal => "Turbo Pascal",
+            Compiler::Watcom => "Watcom C/C++",
+            Compiler::Djgpp => "DJGPP",
+        }
+    }
+}
+
+/// Copyright strings linked in by the C runtime, the year is read from the text following it.
+const COPYRIGHTS: &[(&str, Compiler)] = &[
+    ("MS Run-Time Library - Copyright (c)", Compiler::Microsoft),
+    ("Turbo-C - Copyright (c)", Compiler::TurboC),
+    ("Borland C++ - Copyright", Compiler::BorlandCpp),
+    ("Portions Copyright (c) 1983", Compiler::TurboPascal),
+    ("WATCOM C/C++16 Run-Time system", Compiler::Watcom),
+    ("WATCOM C/C++32 Run-Time system", Compiler::Watcom),
+    ("WATCOM C Run-Time system", Compiler::Watcom),
+];
+
+/// Startup code at the entry point, `??` matches any byte.
+const STARTUP_CODE: &[(&str, &[Compiler])] = &[
+    // mov ah, 30h; int 21h; cmp al, 2; jae ok; int 20h
+    ("B4 30 CD 21 3C 02 73 ?? CD 20", &[Compiler::Microsoft]),
+    // mov dx, DGROUP; mov cs:DGROUP@@, dx; mov ah, 30h; int 21h
+    (
+        "BA ?? ?? 2E 89 16 ?? ?? B4 30 CD 21",
+        &[Compiler::TurboC, Compiler::BorlandCpp],
+    ),
+];
+
+/// DOS extenders, and the compiler if the extender is only used by one.
+const EXTENDERS: &[(&str, &str, Option<Compiler>)] = &[
+    ("DOS/4G", "DOS/4GW", None),
+    ("PMODE/W", "PMODE/W", None),
+    ("CWSDPMI", "CWSDPMI", Some(Compiler::Djgpp)),
+    ("go32stub", "DJGPP go32", Some(Compiler::Djgpp)),
+];
+
+/// Packers identified by a marker at a fixed offset in the MZ header.
+const PACKERS: &[(usize, &str, &str)] = &[
+    (0x1C, "LZ91", "LZEXE 0.91"),
+    (0x1C, "LZ09", "LZEXE 0.90"),
+    (0x1E, "PKLITE", "PKLITE"),
+];
+
+pub fn is_mz(data: &[u8]) -> bool {
+    matches!(data.get(..2), Some(b"MZ") | Some(b"ZM"))
+}
+
+/// Whether the MZ header points to a PE header.
+pub fn is_pe(data: &[u8]) -> bool {
+    let Some(offset) = read_u32(data, 0x3C) else {
+        return false;
+    };
+    let offset = offset as usize;
+
+    is_mz(data)
+        && offset
+            .checked_add(PE_SIGNATURE.len())
+            .and_then(|end| data.get(offset..end))
+            == Some(PE_SIGNATURE)
+}
+
+fn pattern_at(data: &[u8], offset: usize, pattern: &str) -> bool {
+    pattern.split(' ').enumerate().all(|(i, byte)| {
+        let Some(actual) = data.get(offset + i) else {
+            return false;
+        };
+
+        byte == "??" || u8::from_str_radix(byte, 16) == Ok(*actual)
+    })
+}
+
+/// Year in the text at `offset`, the last one is used for ranges such as `1988-1995`.
+fn copyright_year(data: &[u8], offset: usize) -> Option<i32> {
+    let text = data.get(offset..(offset + 80).min(data.len()))?;
+    let end = text.iter().position(|a| *a == 0).unwrap_or(text.len());
+
+    text[..end]
+        .windows(4)
+        .filter(|a| a.iter().all(u8::is_ascii_digit))
+        .filter_map(|a| std::str::from_utf8(a).ok()?.parse::<i32>().ok())
+        .rfind(|a| (1980..2010).contains(a))
+}
+
+fn microsoft_versions(year: i32) -> &'static [CompilerVersion] {
+    match year {
+        1990 | 1991 => &[CompilerVersion::MicrosoftC6_0],
+        1992 => &[CompilerVersion::MicrosoftC7_0],
+        1993 => &[CompilerVersion::VisualCPP1_0, CompilerVersion::VisualCPP1_5],
+        1994.. => &[CompilerVersion::VisualCPP1_5],
+        _ => &[],
+    }
+}
+
+fn add_compiler(compiler: Compiler, year: Option<i32>, info: &mut Info, args: &Args) {
+    match compiler {
+        Compiler::Microsoft => {
+            let versions = year.map(microsoft_versions).unwrap_or_default();
+            if versions.is_empty() {
+                info.add_toolchain(Toolchain::MicrosoftC);
+            } else {
+                let names = versions
+                    .iter()
+                    .map(|a| a.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                print_heuristic(
+                    format!(
+                        "Microsoft runtime copyright year {} suggests compiler is {names}",
+                        year.unwrap_or_default()
+                    ),
+                    args.quiet,
+                );
+                info.insert_multiple_compiler_versions(versions);
+            }
+        }
+        Compiler::TurboC => info.add_toolchain(Toolchain::TurboC),
+        Compiler::BorlandCpp => info.add_toolchain(Toolchain::BorlandCpp),
+        Compiler::TurboPascal => info.add_toolchain(Toolchain::TurboPascal),
+        Compiler::Watcom => info.add_toolchain(Toolchain::Watcom),
+        Compiler::Djgpp => info.add_toolchain(Toolchain::Djgpp),
+    }
+}
+
+fn packer(data: &[u8], args: &Args) -> Option<&'static str> {
+    let packer = PACKERS.iter().find_map(|(offset, marker, name)| {
+        (data.get(*offset..offset + marker.len()) == Some(marker.as_bytes())).then_some(*name)
+    })?;
+
+    print_self_reported(
+        format!("Header contains the {packer} marker, which means the executable is packed and the startup code belongs to the unpacker"),
+        args.quiet,
+    );
+
+    Some(packer)
+}
+
+pub fn versions_from_dos_header(data: &[u8], info: &mut Info, args: &Args) {
+    if !is_mz(data) {
+        print_self_reported("File does not start with an MZ header", args.quiet);
+        info.set_likely_incorrect(LikelyIncorrect::Malformed);
+        return;
+    }
+
+    let (Some(header_paragraphs), Some(ip), Some(cs)) = (
+        read_u16(data, 0x08),
+        read_u16(data, 0x14),
+        read_u16(data, 0x16),
+    ) else {
+        print_self_reported("MZ header is truncated", args.quiet);
+        info.set_likely_incorrect(LikelyIncorrect::Malformed);
+        return;
+    };
+
+    print_self_reported(
+        "MZ header without a PE or NE header means this is a DOS executable",
+        args.quiet,
+    );
+    print_self_reported(
+        "DOS executables do not have a timestamp, so versions can not be checked against the compile date",
+        args.quiet,
+    );
+    info.set_os(OperatingSystem::MsDos);
+    info.set_subsystem(OperatingSystem::MsDos);
+
+    let packed = packer(data, args).is_some();
+
+    for (marker, extender, compiler) in EXTENDERS {
+        if find_bytes(data, marker.as_bytes()).is_some() {
+            print_heuristic(
+                format!("String '{marker}' suggests the program uses the {extender} DOS extender"),
+                args.quiet,
+            );
+            if let Some(compiler) = compiler {
+                add_compiler(*compiler, None, info, args);
+            }
+        }
+    }
+
+    let mut era = None;
+    let mut identified = Vec::new();
+    for (copyright, compiler) in COPYRIGHTS {
+        let Some(offset) = find_bytes(data, copyright.as_bytes()) else {
+            continue;
+        };
+        let year = copyright_year(data, offset + copyright.len());
+
+        match year {
+            None => print_heuristic(
+                format!("String '{copyright}' suggests compiler is {}", compiler.name()),
+                args.quiet,
+            ),
+            Some(year) => print_heuristic(
+                format!(
+                    "String '{copyright}' with year {year} suggests compiler is {} from around {year}",
+                    compiler.name()
+                ),
+                args.quiet,
+            ),
+        }
+
+        era = era.max(year);
+        identified.push(*compiler);
+        add_compiler(*compiler, year, info, args);
+    }
+
+    // The initial CS is relative to the start of the load module, which follows the header.
+    let entry = (header_paragraphs as usize * 16 + cs as usize * 16 + ip as usize) & 0xFFFFF;
+    match data.get(entry..) {
+        None => {
+            print_self_reported(
+                format!("Entry point {cs:04X}:{ip:04X} is outside of the file"),
+                args.quiet,
+            );
+            info.set_likely_incorrect(LikelyIncorrect::Malformed);
+        }
+        Some(_) if packed => {}
+        Some(code) => {
+            for (pattern, compilers) in STARTUP_CODE {
+                if !pattern_at(data, entry, pattern) {
+                    continue;
+                }
+
+                // Copyright strings are more specific than the startup code shared by several versions.
+                if compilers.iter().any(|a| identified.contains(a)) {
+                    continue;
+                }
+
+                let names = compilers
+                    .iter()
+                    .map(|a| a.name())
+                    .collect::<Vec<_>>()
+                    .join(" or ");
+                print_heuristic(
+                    format!("Startup code at the entry point suggests compiler is {names}"),
+                    args.quiet,
+                );
+                for compiler in *compilers {
+                    add_compiler(*compiler, None, info, args);
+                }
+            }
+
+            let window = &code[..code.len().min(ENTRY_WINDOW)];
+            if find_bytes(window, b"WATCOM").is_some() && !identified.contains(&Compiler::Watcom) {
+                print_heuristic(
+                    "Startup code jumps over a WATCOM banner which suggests compiler is Watcom C/C++",
+                    args.quiet,
+                );
+                add_compiler(Compiler::Watcom, None, info, args);
+            }
+        }
+    }
+
+    if let Some(year) = era {
+        info.set_era(year);
+    }
+}
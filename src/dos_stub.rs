@@ -0,0 +1,190 @@
+use crate::info::Info;
+use crate::read::{find_bytes, read_u16, read_u32, read_u8};
+use crate::toolchain::Toolchain;
+use crate::{print_heuristic, Args};
+
+const DOS_HEADER_SIZE: usize = 0x40;
+const RICH_MAGIC: &[u8] = b"Rich";
+const DANS_MAGIC: u32 = 0x536E6144;
+
+const MICROSOFT_STUB: &[u8] = b"\x0e\x1f\xba\x0e\x00\xb4\x09\xcd\x21\xb8\x01\x4c\xcd\x21This program cannot be run in DOS mode.\r\r\n$";
+const LLD_STUB: &[u8] = b"\x0e\x1f\xba\x0e\x00\xb4\x09\xcd\x21\xb8\x01\x4c\xcd\x21This program cannot be run in DOS mode.$";
+const BORLAND_STUB: &[u8] = b"\xba\x10\x00\x0e\x1f\xb4\x09\xcd\x21\xb8\x01\x4c\xcd\x21\x90\x90This program must be run under Win32\r\n$";
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Stub {
+    /// Written by the Microsoft linker and GNU ld, which only differ by the Rich header.
+    Microsoft,
+    Lld,
+    Borland,
+    /// Stubs made for NE executables by the 16-bit Microsoft linker.
+    Windows16,
+}
+
+const fn fnv1a(data: &[u8]) -> u64 {
+    let mut hash = 0xcbf29ce484222325_u64;
+    let mut i = 0;
+    while i < data.len() {
+        hash ^= data[i] as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+        i += 1;
+    }
+
+    hash
+}
+
+/// Hashes of complete stubs with trailing zero padding removed.
+const STUB_HASHES: &[(u64, Stub)] = &[
+    (fnv1a(MICROSOFT_STUB), Stub::Microsoft),
+    (fnv1a(LLD_STUB), Stub::Lld),
+    (fnv1a(BORLAND_STUB), Stub::Borland),
+];
+
+/// Messages printed by the stubs, used when the stub does not match a known hash.
+const STUB_MESSAGES: &[(&str, Stub)] = &[
+    (
+        "This program cannot be run in DOS mode.\r\r\n$",
+        Stub::Microsoft,
+    ),
+    ("This program cannot be run in DOS mode.$", Stub::Lld),
+    ("This program must be run under Win32", Stub::Borland),
+    ("This program requires Microsoft Windows", Stub::Windows16),
+    (
+        "This program must be run under Microsoft Windows",
+        Stub::Windows16,
+    ),
+];
+
+/// Offset of the `DanS` marker that starts the Rich header, which is stored after the stub.
+pub fn rich_header_offset(data: &[u8], e_lfanew: usize) -> Option<usize> {
+    let header = data.get(..e_lfanew)?;
+    let rich = DOS_HEADER_SIZE + find_bytes(header.get(DOS_HEADER_SIZE..)?, RICH_MAGIC)?;
+    let key = read_u32(header, rich + 4)?;
+
+    (DOS_HEADER_SIZE..rich)
+        .step_by(4)
+        .find(|a| read_u32(header, *a) == Some(DANS_MAGIC ^ key))
+}
+
+/// Something only GNU ld writes, which tells it apart from Microsoft linkers that did not write a Rich header.
+fn gnu_ld_evidence(data: &[u8], e_lfanew: usize) -> Option<String> {
+    // GNU ld writes the binutils version, the Microsoft linkers of Visual C++ 2.x wrote 2.50 to 2.60.
+    let major = read_u8(data, e_lfanew + 26)?;
+    let minor = read_u8(data, e_lfanew + 27)?;
+    if major == 2 && (20..50).contains(&minor) {
+        return Some(format!("linker version {major}.{minor}"));
+    }
+
+    // MinGW keeps DWARF sections, whose long names are written as an offset into the string table such as `/4`.
+    let sections = read_u16(data, e_lfanew + 6)? as usize;
+    let table = e_lfanew + 24 + read_u16(data, e_lfanew + 20)? as usize;
+    (0..sections)
+        .filter_map(|a| data.get(table + a * 40..table + a * 40 + 8))
+        .map(|a| {
+            String::from_utf8_lossy(a)
+                .trim_end_matches('\0')
+                .to_string()
+        })
+        .find(|a| {
+            a == ".eh_frame"
+                || a.strip_prefix('/')
+                    .is_some_and(|a| !a.is_empty() && a.bytes().all(|a| a.is_ascii_digit()))
+        })
+        .map(|a| format!("section '{a}'"))
+}
+
+fn classify(stub: &[u8]) -> Option<(Stub, bool)> {
+    let hash = fnv1a(stub);
+    if let Some((_, kind)) = STUB_HASHES.iter().find(|(a, _)| *a == hash) {
+        return Some((*kind, true));
+    }
+
+    STUB_MESSAGES
+        .iter()
+        .find(|(message, _)| find_bytes(stub, message.as_bytes()).is_some())
+        .map(|(_, kind)| (*kind, false))
+}
+
+pub fn versions_from_dos_stub(data: &[u8], info: &mut Info, args: &Args) {
+    let Some(e_lfanew) = read_u32(data, 0x3C).map(|a| a as usize) else {
+        return;
+    };
+
+    let rich_header = rich_header_offset(data, e_lfanew);
+    let end = rich_header.unwrap_or(e_lfanew);
+    let Some(stub) = data.get(DOS_HEADER_SIZE..end) else {
+        return;
+    };
+    let length = stub.iter().rposition(|a| *a != 0).map_or(0, |a| a + 1);
+    let stub = &stub[..length];
+
+    if stub.is_empty() {
+        print_heuristic(
+            "Executable has no DOS stub, which suggests a custom linker or post-link modification",
+            args.quiet,
+        );
+        return;
+    }
+
+    let Some((kind, exact)) = classify(stub) else {
+        print_heuristic(
+            format!("DOS stub of {length} bytes is unknown, which suggests a custom /STUB or post-link modification"),
+            args.quiet,
+        );
+        return;
+    };
+
+    // The stub is only used as a hint for the toolchain when nothing more specific has been found.
+    let add_toolchain = |toolchain: Toolchain, info: &mut Info| {
+        if info.toolchains().is_empty() && info.dotnet().is_none() {
+            info.add_toolchain(toolchain);
+        }
+    };
+
+    if !exact {
+        print_heuristic(
+            "DOS stub has a known message but unusual code, which suggests a custom /STUB or post-link modification",
+            args.quiet,
+        );
+    }
+
+    match (kind, rich_header.is_some()) {
+        (Stub::Microsoft, true) => print_heuristic(
+            "DOS stub and Rich header suggest linker is Microsoft LINK",
+            args.quiet,
+        ),
+        (Stub::Microsoft, false) if info.dotnet().is_some() => print_heuristic(
+            "DOS stub of Microsoft LINK without a Rich header is expected for .NET compilers",
+            args.quiet,
+        ),
+        (Stub::Microsoft, false) => match gnu_ld_evidence(data, e_lfanew) {
+            Some(evidence) => {
+                print_heuristic(
+                    format!("DOS stub of Microsoft LINK without a Rich header and {evidence} suggest linker is GNU ld"),
+                    args.quiet,
+                );
+                add_toolchain(Toolchain::Gnu, info);
+            }
+            // Microsoft linkers before Visual C++ 6.0 did not write a Rich header, and it can be removed.
+            None => print_heuristic(
+                "DOS stub of Microsoft LINK without a Rich header suggests the linker predates the Rich header or that it was removed",
+                args.quiet,
+            ),
+        },
+        (Stub::Lld, _) => {
+            print_heuristic("DOS stub suggests linker is LLVM lld", args.quiet);
+            add_toolchain(Toolchain::Llvm, info);
+        }
+        (Stub::Borland, _) => {
+            print_heuristic(
+                "DOS stub suggests linker is Borland TLINK32 or ILINK32",
+                args.quiet,
+            );
+            add_toolchain(Toolchain::Borland32, info);
+        }
+        (Stub::Windows16, _) => print_heuristic(
+            "DOS stub is meant for 16-bit Windows executables, which suggests a custom /STUB or post-link modification",
+            args.quiet,
+        ),
+    }
+}
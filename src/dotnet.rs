@@ -0,0 +1,231 @@
+use crate::clr_metadata::{target_framework_moniker, Metadata};
+use crate::dotnet_native::ready_to_run_from_clr_header;
+use crate::info::Info;
+use crate::likely_incorrect::LikelyIncorrect;
+use crate::read::{read_rva, read_u16, read_u32};
+use crate::{print_heuristic, print_self_reported, Args};
+use exe::{ImageDirectoryEntry, PtrPE, PE};
+use std::fmt::Formatter;
+
+const COMIMAGE_FLAGS_ILONLY: u32 = 0x00000001;
+const COMIMAGE_FLAGS_32BITREQUIRED: u32 = 0x00000002;
+const COMIMAGE_FLAGS_IL_LIBRARY: u32 = 0x00000004;
+const COMIMAGE_FLAGS_STRONGNAMESIGNED: u32 = 0x00000008;
+const COMIMAGE_FLAGS_NATIVE_ENTRYPOINT: u32 = 0x00000010;
+const COMIMAGE_FLAGS_TRACKDEBUGDATA: u32 = 0x00010000;
+const COMIMAGE_FLAGS_32BITPREFERRED: u32 = 0x00020000;
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum AssemblyKind {
+    PureIl,
+    MixedMode,
+}
+
+impl std::fmt::Display for AssemblyKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            AssemblyKind::PureIl => "Pure IL",
+            AssemblyKind::MixedMode => "Mixed-mode (C++/CLI)",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct DotNet {
+    pub metadata_version: Option<String>,
+    pub target_framework: Option<String>,
+    pub kind: AssemblyKind,
+    pub strong_name_signed: bool,
+}
+
+/// Information about the version of the CLR the metadata was written for.
+fn runtime_from_metadata_version(version: &str) -> Option<&'static str> {
+    Some(match version {
+        "v1.0.3705" => ".NET Framework 1.0",
+        "v1.1.4322" => ".NET Framework 1.1",
+        "v2.0.50727" => "CLR 2.0 (.NET Framework 2.0 to 3.5)",
+        "v4.0.30319" => "CLR 4.0 (.NET Framework 4.x, .NET Core or .NET 5 and newer)",
+        _ => {
+            return None;
+        }
+    })
+}
+
+fn flag_names(flags: u32) -> Vec<&'static str> {
+    [
+        (COMIMAGE_FLAGS_ILONLY, "ILONLY"),
+        (COMIMAGE_FLAGS_32BITREQUIRED, "32BITREQUIRED"),
+        (COMIMAGE_FLAGS_IL_LIBRARY, "IL_LIBRARY"),
+        (COMIMAGE_FLAGS_STRONGNAMESIGNED, "STRONGNAMESIGNED"),
+        (COMIMAGE_FLAGS_NATIVE_ENTRYPOINT, "NATIVE_ENTRYPOINT"),
+        (COMIMAGE_FLAGS_TRACKDEBUGDATA, "TRACKDEBUGDATA"),
+        (COMIMAGE_FLAGS_32BITPREFERRED, "32BITPREFERRED"),
+    ]
+    .into_iter()
+    .filter(|(flag, _)| flags & flag != 0)
+    .map(|(_, name)| name)
+    .collect()
+}
+
+fn platform_target(flags: u32) -> &'static str {
+    let required = flags & COMIMAGE_FLAGS_32BITREQUIRED != 0;
+    let preferred = flags & COMIMAGE_FLAGS_32BITPREFERRED != 0;
+
+    match (required, preferred) {
+        (true, true) => "Any CPU (32-bit preferred)",
+        (true, false) => "x86",
+        (false, _) => "Any CPU or a specific 64-bit platform",
+    }
+}
+
+pub fn versions_from_clr_header(pe: &PtrPE, info: &mut Info, args: &Args) {
+    let Ok(directory) = pe.get_data_directory(ImageDirectoryEntry::COMDescriptor) else {
+        return;
+    };
+    let Some(header) = read_rva(pe, directory.virtual_address.0, 0x48) else {
+        return;
+    };
+
+    let (Some(major), Some(minor), Some(flags)) = (
+        read_u16(header, 4),
+        read_u16(header, 6),
+        read_u32(header, 16),
+    ) else {
+        return;
+    };
+
+    print_self_reported(
+        format!("CLR header with runtime version {major}.{minor} means this is a .NET assembly"),
+        args.quiet,
+    );
+
+    let flags_string = flag_names(flags).join(" | ");
+    print_self_reported(
+        format!(
+            "CLR flags are {flags_string}, which means platform target is {}",
+            platform_target(flags)
+        ),
+        args.quiet,
+    );
+
+    // Precompiled assemblies clear ILONLY without containing any C++.
+    let ready_to_run = ready_to_run_from_clr_header(pe, header, info, args);
+
+    let kind = if flags & COMIMAGE_FLAGS_ILONLY != 0 {
+        print_self_reported(
+            "CLR flags contain ILONLY which means the assembly is pure IL",
+            args.quiet,
+        );
+        AssemblyKind::PureIl
+    } else if ready_to_run || flags & COMIMAGE_FLAGS_IL_LIBRARY != 0 {
+        print_self_reported(
+            "CLR flags do not contain ILONLY because the IL is precompiled, which means the assembly is still compiled from IL",
+            args.quiet,
+        );
+        AssemblyKind::PureIl
+    } else {
+        print_heuristic(
+            "CLR flags do not contain ILONLY which suggests a mixed-mode C++/CLI assembly",
+            args.quiet,
+        );
+        info.set_cpp(true);
+        AssemblyKind::MixedMode
+    };
+
+    let strong_name_signature_size = read_u32(header, 36).unwrap_or(0);
+    let strong_name_signed =
+        flags & COMIMAGE_FLAGS_STRONGNAMESIGNED != 0 && strong_name_signature_size != 0;
+    if strong_name_signed {
+        print_self_reported(
+            format!(
+                "Assembly is strong-name signed with a {strong_name_signature_size} byte signature"
+            ),
+            args.quiet,
+        );
+    } else if strong_name_signature_size != 0 {
+        print_self_reported(
+            "Assembly has space for a strong-name signature but is not signed, which suggests delay signing",
+            args.quiet,
+        );
+    }
+
+    let metadata = match (read_u32(header, 8), read_u32(header, 12)) {
+        (Some(rva), Some(size)) => read_rva(pe, rva, size).and_then(Metadata::parse),
+        _ => None,
+    };
+
+    let Some(metadata) = metadata else {
+        print_self_reported("Unable to parse .NET metadata", args.quiet);
+        info.set_likely_incorrect(LikelyIncorrect::Malformed);
+        info.set_dotnet(DotNet {
+            metadata_version: None,
+            target_framework: None,
+            kind,
+            strong_name_signed,
+        });
+        return;
+    };
+
+    let version = &metadata.version;
+    match runtime_from_metadata_version(version) {
+        None => print_self_reported(
+            format!("Metadata version string is unknown: '{version}'"),
+            args.quiet,
+        ),
+        Some(runtime) => print_self_reported(
+            format!("Metadata version string is {version}, which suggests runtime is {runtime}"),
+            args.quiet,
+        ),
+    }
+
+    if let Some(assembly) = metadata.assembly() {
+        let name = &assembly.name;
+        let assembly_version = &assembly.version;
+        print_self_reported(
+            format!("Assembly name is '{name}' with version {assembly_version}"),
+            args.quiet,
+        );
+
+        if assembly.has_public_key && !strong_name_signed {
+            print_self_reported(
+                "Assembly has a public key but no strong-name signature",
+                args.quiet,
+            );
+        }
+    }
+
+    let target_framework = metadata.target_framework().map(|framework| {
+        match target_framework_moniker(&framework) {
+            None => {
+                print_self_reported(
+                    format!("TargetFrameworkAttribute is '{framework}'"),
+                    args.quiet,
+                );
+                framework
+            }
+            Some(moniker) => {
+                print_self_reported(
+                    format!("TargetFrameworkAttribute is '{framework}', which means target framework is {moniker}"),
+                    args.quiet,
+                );
+                moniker
+            }
+        }
+    });
+
+    for reference in metadata.assembly_references() {
+        let name = &reference.name;
+        let reference_version = &reference.version;
+        print_self_reported(
+            format!("References assembly '{name}' version {reference_version}"),
+            args.quiet,
+        );
+    }
+
+    info.set_dotnet(DotNet {
+        metadata_version: Some(metadata.version.clone()),
+        target_framework,
+        kind,
+        strong_name_signed,
+    });
+}
@@ -0,0 +1,300 @@
+use crate::info::Info;
+use crate::likely_incorrect::LikelyIncorrect;
+use crate::read::{find_bytes, read_rva, read_u16, read_u32, read_u64, read_u8};
+use crate::toolchain::{DotNetForm, Toolchain};
+use crate::{print_heuristic, print_self_reported, Args};
+use exe::{Buffer, CCharString, ImageExportDirectory, PtrPE, PE};
+
+const READY_TO_RUN_SIGNATURE: &[u8] = b"RTR\0";
+
+/// SHA-256 of ".net core bundle", placed directly after the bundle header offset in an apphost.
+const BUNDLE_SIGNATURE: [u8; 32] = [
+    0x8b, 0x12, 0x02, 0xb9, 0x6a, 0x61, 0x20, 0x38, 0x72, 0x7b, 0x93, 0x02, 0x14, 0xd7, 0xa0, 0x32,
+    0x13, 0xf5, 0xb9, 0xe6, 0xef, 0xae, 0x33, 0x18, 0xee, 0x3b, 0x2d, 0xce, 0x24, 0xb3, 0x6a, 0xae,
+];
+
+const NATIVE_AOT_SECTIONS: &[&str] = &[".managed", "hydrated"];
+const NATIVE_AOT_EXPORT: &str = "DotNetRuntimeDebugHeader";
+
+fn runtime_from_ready_to_run_version(major: u16) -> Option<&'static str> {
+    Some(match major {
+        1 => ".NET Core 1.x",
+        2 => ".NET Core 2.x",
+        3 => ".NET Core 3.x",
+        4 => ".NET 5",
+        5 => ".NET 6",
+        6 | 7 => ".NET 7",
+        8 => ".NET 8",
+        9 => ".NET 8 or .NET 9",
+        10.. => ".NET 10 or newer",
+        _ => {
+            return None;
+        }
+    })
+}
+
+fn runtime_from_bundle_version(major: u32) -> Option<&'static str> {
+    Some(match major {
+        1 => ".NET Core 3.x",
+        2 => ".NET 5",
+        6.. => ".NET 6 or newer",
+        _ => {
+            return None;
+        }
+    })
+}
+
+/// Reports the ReadyToRun header that the managed native header points to, returning whether there is one.
+pub fn ready_to_run_from_clr_header(
+    pe: &PtrPE,
+    clr_header: &[u8],
+    info: &mut Info,
+    args: &Args,
+) -> bool {
+    let (Some(rva), Some(size)) = (read_u32(clr_header, 0x40), read_u32(clr_header, 0x44)) else {
+        return false;
+    };
+    let Some(header) = read_rva(pe, rva, size.max(16)) else {
+        return false;
+    };
+    if header.get(..4) != Some(READY_TO_RUN_SIGNATURE) {
+        return false;
+    }
+
+    let (Some(major), Some(minor)) = (read_u16(header, 4), read_u16(header, 6)) else {
+        return true;
+    };
+
+    match runtime_from_ready_to_run_version(major) {
+        None => print_self_reported(
+            format!("ReadyToRun header has unknown version {major}.{minor}"),
+            args.quiet,
+        ),
+        Some(runtime) => {
+            print_self_reported(
+                format!("ReadyToRun header has version {major}.{minor}, which suggests runtime is {runtime}"),
+                args.quiet,
+            );
+            info.add_toolchain(Toolchain::DotNet {
+                runtime: runtime.to_string(),
+                form: DotNetForm::ReadyToRun,
+            });
+        }
+    }
+
+    true
+}
+
+/// Reads a string prefixed with a 7-bit encoded length as written by .NET's `BinaryWriter`.
+fn read_prefixed_string(data: &[u8], offset: usize) -> Option<(String, usize)> {
+    let mut length = 0_usize;
+    let mut size = 0;
+    loop {
+        let byte = read_u8(data, offset + size)?;
+        length |= ((byte & 0x7F) as usize) << (7 * size);
+        size += 1;
+
+        if byte & 0x80 == 0 {
+            break;
+        }
+        if size == 5 {
+            return None;
+        }
+    }
+
+    let start = offset + size;
+    let s = data.get(start..start.checked_add(length)?)?;
+    Some((String::from_utf8_lossy(s).into_owned(), size + length))
+}
+
+/// Finds the version of `Microsoft.NETCore.App` in a `runtimeconfig.json`.
+fn runtime_version_from_config(config: &str) -> Option<String> {
+    let framework = config.find("Microsoft.NETCore.App")?;
+    let rest = &config[framework..];
+    let version = rest.find("\"version\"")?;
+    let rest = &rest[version + "\"version\"".len()..];
+    let rest = rest.trim_start().strip_prefix(':')?.trim_start();
+    let rest = rest.strip_prefix('"')?;
+
+    Some(rest[..rest.find('"')?].to_string())
+}
+
+fn bundle_type_name(kind: u8) -> &'static str {
+    match kind {
+        1 => "assembly",
+        2 => "native binary",
+        3 => "deps.json",
+        4 => "runtimeconfig.json",
+        5 => "symbols",
+        _ => "unknown",
+    }
+}
+
+fn single_file_bundle(data: &[u8], info: &mut Info, args: &Args) {
+    let Some(signature) = find_bytes(data, &BUNDLE_SIGNATURE) else {
+        return;
+    };
+    let Some(header_offset) = signature.checked_sub(8).and_then(|a| read_u64(data, a)) else {
+        return;
+    };
+
+    if header_offset == 0 {
+        print_self_reported(
+            "Contains a .NET apphost bundle marker without a bundle, which means this is a framework-dependent apphost",
+            args.quiet,
+        );
+        return;
+    }
+
+    let offset = header_offset as usize;
+    let (Some(major), Some(minor), Some(file_amount)) = (
+        read_u32(data, offset),
+        read_u32(data, offset + 4),
+        read_u32(data, offset + 8),
+    ) else {
+        print_self_reported(
+            format!("Bundle header offset {header_offset:#x} is outside of the file"),
+            args.quiet,
+        );
+        info.set_likely_incorrect(LikelyIncorrect::Malformed);
+        return;
+    };
+
+    let Some((bundle_id, size)) = read_prefixed_string(data, offset + 12) else {
+        return;
+    };
+    let mut offset = offset + 12 + size;
+
+    print_self_reported(
+        format!("Contains a .NET single-file bundle with version {major}.{minor}, id '{bundle_id}' and {file_amount} embedded files"),
+        args.quiet,
+    );
+
+    let mut runtime_config = None;
+    if major >= 2 {
+        if let (Some(config_offset), Some(config_size)) =
+            (read_u64(data, offset + 16), read_u64(data, offset + 24))
+        {
+            runtime_config = config_offset
+                .checked_add(config_size)
+                .and_then(|end| data.get(config_offset as usize..end as usize))
+                .map(|a| String::from_utf8_lossy(a).into_owned());
+        }
+
+        offset += 40;
+    }
+
+    for _ in 0..file_amount {
+        let Some(size) = read_u64(data, offset + 8) else {
+            break;
+        };
+        offset += 16;
+        if major >= 6 {
+            offset += 8;
+        }
+        let Some(kind) = read_u8(data, offset) else {
+            break;
+        };
+        let Some((path, length)) = read_prefixed_string(data, offset + 1) else {
+            break;
+        };
+        offset += 1 + length;
+
+        print_self_reported(
+            format!(
+                "Bundle contains {} '{path}' ({size} bytes)",
+                bundle_type_name(kind)
+            ),
+            args.quiet,
+        );
+    }
+
+    let runtime = if let Some(version) = runtime_config
+        .as_deref()
+        .and_then(runtime_version_from_config)
+    {
+        print_self_reported(
+            format!(
+                "Bundled runtimeconfig.json references Microsoft.NETCore.App version {version}"
+            ),
+            args.quiet,
+        );
+        Some(format!(".NET {version}"))
+    } else if let Some(runtime) = runtime_from_bundle_version(major) {
+        print_heuristic(
+            format!("Bundle version {major}.{minor} suggests runtime is {runtime}"),
+            args.quiet,
+        );
+        Some(runtime.to_string())
+    } else {
+        None
+    };
+
+    if let Some(runtime) = runtime {
+        info.add_toolchain(Toolchain::DotNet {
+            runtime,
+            form: DotNetForm::SingleFileBundle,
+        });
+    }
+}
+
+fn native_aot(pe: &PtrPE, info: &mut Info, args: &Args) {
+    let mut found = false;
+
+    if let Ok(sections) = pe.get_section_table() {
+        for section in sections {
+            let Ok(name) = section.name.as_str() else {
+                continue;
+            };
+            let name = name.trim_end_matches('\0');
+
+            if NATIVE_AOT_SECTIONS.contains(&name) {
+                found = true;
+                print_heuristic(
+                    format!("Section '{name}' suggests a .NET NativeAOT image"),
+                    args.quiet,
+                );
+            }
+        }
+    }
+
+    if let Ok(exports) = ImageExportDirectory::parse(pe).and_then(|a| a.get_export_map(pe)) {
+        if exports.contains_key(NATIVE_AOT_EXPORT) {
+            found = true;
+            print_heuristic(
+                format!("Export '{NATIVE_AOT_EXPORT}' suggests a .NET NativeAOT image"),
+                args.quiet,
+            );
+        }
+    }
+
+    if !found {
+        return;
+    }
+
+    // NativeAOT does not have a CLR header, but still emits a ReadyToRun header into read only data.
+    let version = pe.get_section_by_name(".rdata").ok().and_then(|section| {
+        let data = section.read(pe).ok()?;
+        let offset = find_bytes(data, READY_TO_RUN_SIGNATURE)?;
+        Some((read_u16(data, offset + 4)?, read_u16(data, offset + 6)?))
+    });
+
+    if let Some((major, minor)) = version {
+        print_self_reported(
+            format!("NativeAOT ReadyToRun header has version {major}.{minor}"),
+            args.quiet,
+        );
+    }
+
+    // NativeAOT was introduced with .NET 7. Its header is versioned separately from the CoreCLR one,
+    // so the version is not mapped to a release.
+    info.add_toolchain(Toolchain::DotNet {
+        runtime: ".NET 7 or newer".to_string(),
+        form: DotNetForm::NativeAot,
+    });
+}
+
+pub fn versions_from_dotnet_host(pe: &PtrPE, info: &mut Info, args: &Args) {
+    single_file_bundle(pe.as_slice(), info, args);
+    native_aot(pe, info, args);
+}
@@ -0,0 +1,295 @@
+use crate::info::Info;
+use crate::optional_header::OptionalHeader;
+use crate::read::{find_bytes, read_u32};
+use crate::versions_for_operating_system::OperatingSystem;
+use crate::{print_heuristic, print_self_reported, Args};
+use exe::{Arch, Buffer, CCharString, ImportData, ImportDirectory, Offset, PtrPE, PE};
+
+pub const IMAGE_SUBSYSTEM_NATIVE: u16 = 1;
+
+const KERNEL_MODULES: &[&str] = &[
+    "ntoskrnl.exe",
+    "hal.dll",
+    "wdfldr.sys",
+    "ndis.sys",
+    "storport.sys",
+    "scsiport.sys",
+    "classpnp.sys",
+    "videoprt.sys",
+    "portcls.sys",
+    "ks.sys",
+    "usbd.sys",
+    "fltmgr.sys",
+    "wmilib.sys",
+    "tdi.sys",
+    "netio.sys",
+    "ksecdd.sys",
+    "cng.sys",
+    "wdmsec.sys",
+];
+
+/// Kernel routines and the first version of Windows that exports them.
+const KERNEL_ROUTINES: &[(&str, OperatingSystem)] = &[
+    ("PoCallDriver", OperatingSystem::Windows2000),
+    ("PoStartNextPowerIrp", OperatingSystem::Windows2000),
+    ("IoRegisterDeviceInterface", OperatingSystem::Windows2000),
+    ("IoWMIRegistrationControl", OperatingSystem::Windows2000),
+    ("KeAcquireInStackQueuedSpinLock", OperatingSystem::WindowsXP),
+    ("IoVolumeDeviceToDosName", OperatingSystem::WindowsXP),
+    ("IoCsqInitialize", OperatingSystem::WindowsXP),
+    ("EtwRegister", OperatingSystem::WindowsVista),
+    ("EtwWrite", OperatingSystem::WindowsVista),
+    ("KeQueryActiveProcessorCount", OperatingSystem::WindowsVista),
+    ("CmRegisterCallbackEx", OperatingSystem::WindowsVista),
+    ("ObRegisterCallbacks", OperatingSystem::WindowsVista),
+    (
+        "PsSetCreateProcessNotifyRoutineEx",
+        OperatingSystem::WindowsVista,
+    ),
+    ("KeQueryActiveProcessorCountEx", OperatingSystem::Windows7),
+    ("KeGetCurrentProcessorNumberEx", OperatingSystem::Windows7),
+    ("KeQueryMaximumProcessorCountEx", OperatingSystem::Windows7),
+    ("KeSetSystemGroupAffinityThread", OperatingSystem::Windows7),
+    ("IoSetDevicePropertyData", OperatingSystem::Windows7),
+    ("PoFxRegisterDevice", OperatingSystem::Windows8),
+    (
+        "ExGetFirmwareEnvironmentVariable",
+        OperatingSystem::Windows8,
+    ),
+    ("ExAllocateTimer", OperatingSystem::Windows8_1),
+    ("ExSetTimer", OperatingSystem::Windows8_1),
+    (
+        "PsSetCreateProcessNotifyRoutineEx2",
+        OperatingSystem::Windows10,
+    ),
+    ("ExAllocatePool2", OperatingSystem::Windows10),
+    ("ExAllocatePool3", OperatingSystem::Windows10),
+];
+
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Driver {
+    pub user_mode: bool,
+    pub framework: Option<String>,
+    pub driver_kit: Option<&'static str>,
+    pub minimum_from_imports: Option<OperatingSystem>,
+}
+
+fn driver_kit_from_framework_version(major: u32, minor: u32) -> Option<&'static str> {
+    Some(match (major, minor) {
+        (1, 0..=1) => "Windows Server 2003 SP1 DDK",
+        (1, 5) => "WDK 6000 (Windows Vista)",
+        (1, 7) => "WDK 6001 (Windows Vista SP1)",
+        (1, 9) => "WDK 7600 (Windows 7)",
+        (1, 11) => "WDK 8.0",
+        (1, 13) | (2, 0) => "WDK 8.1",
+        (1 | 2, 15) => "WDK 10 (1507)",
+        (1 | 2, 17) => "WDK 10 (1511)",
+        (1 | 2, 19) => "WDK 10 (1607)",
+        (1 | 2, 21) => "WDK 10 (1703)",
+        (1 | 2, 23) => "WDK 10 (1709)",
+        (1 | 2, 25) => "WDK 10 (1803)",
+        (1 | 2, 27) => "WDK 10 (1809)",
+        (1 | 2, 29) => "WDK 10 (1903)",
+        (1 | 2, 31) => "WDK 10 (2004)",
+        (1 | 2, 33) => "WDK for Windows 11",
+        _ => {
+            return None;
+        }
+    })
+}
+
+/// Driver kits shipped their own compiler and linker, so the linker version identifies them for WDM drivers.
+fn driver_kit_from_linker_version(major: u8, minor: u8) -> Option<&'static str> {
+    Some(match (major, minor) {
+        (5 | 6, _) => "Windows NT 4.0 or Windows 2000 DDK",
+        (7, 0) => "Windows XP DDK",
+        (7, 10) => "Windows Server 2003 DDK",
+        (8, _) => "WDK 6000 or 6001 (Windows Vista)",
+        (9, _) => "WDK 7600 (Windows 7)",
+        (11, _) => "WDK 8.0",
+        (12, _) => "WDK 8.1",
+        (14, _) => "WDK 10 or newer",
+        _ => {
+            return None;
+        }
+    })
+}
+
+/// Reads the version from the `WDF_BIND_INFO` passed to `WdfVersionBind`.
+///
+/// The structure is found through a pointer to the UTF-16 component name.
+fn framework_version(pe: &PtrPE, component: &str) -> Option<(u32, u32, u32)> {
+    let data = pe.as_slice();
+    let name = component
+        .encode_utf16()
+        .flat_map(|a| a.to_le_bytes())
+        .collect::<Vec<_>>();
+
+    let offset = find_bytes(data, &name)?;
+    let rva = pe.offset_to_rva(Offset(offset as u32)).ok()?;
+    let va = pe.get_image_base().ok()? + rva.0 as u64;
+
+    let (pointer, pointer_size) = match pe.get_arch().ok()? {
+        Arch::X86 => ((va as u32).to_le_bytes().to_vec(), 4),
+        Arch::X64 => (va.to_le_bytes().to_vec(), 8),
+    };
+
+    // Code also references the name, so only accept pointers inside a plausible structure.
+    let mut start = 0;
+    while let Some(found) = find_bytes(&data[start..], &pointer) {
+        let component = start + found;
+        start = component + 1;
+
+        let Some(size) = component
+            .checked_sub(pointer_size)
+            .and_then(|a| read_u32(data, a))
+        else {
+            continue;
+        };
+        let version = component + pointer_size;
+        let (Some(major), Some(minor), Some(build)) = (
+            read_u32(data, version),
+            read_u32(data, version + 4),
+            read_u32(data, version + 8),
+        ) else {
+            continue;
+        };
+
+        if (0x10..=0x80).contains(&size) && (1..=2).contains(&major) && minor < 100 {
+            return Some((major, minor, build));
+        }
+    }
+
+    None
+}
+
+/// The framework version from the WdfVersionBind data and the driver kit it suggests.
+fn framework(
+    pe: &PtrPE,
+    component: &str,
+    name: &str,
+    args: &Args,
+) -> Option<(String, Option<&'static str>)> {
+    let (major, minor, build) = framework_version(pe, component)?;
+
+    let driver_kit = driver_kit_from_framework_version(major, minor);
+    match driver_kit {
+        None => print_self_reported(
+            format!("WdfVersionBind data has unknown {name} version {major}.{minor} (build {build})"),
+            args.quiet,
+        ),
+        Some(kit) => print_self_reported(
+            format!("WdfVersionBind data has {name} version {major}.{minor} (build {build}), which suggests {kit}"),
+            args.quiet,
+        ),
+    }
+
+    Some((format!("{name} {major}.{minor}"), driver_kit))
+}
+
+pub fn versions_from_driver(pe: &PtrPE, header: &OptionalHeader, info: &mut Info, args: &Args) {
+    // UMDF drivers are user mode DLLs, so they have a Windows subsystem.
+    if header.subsystem != IMAGE_SUBSYSTEM_NATIVE {
+        if let Some((framework, driver_kit)) = framework(pe, "UmdfLibrary", "UMDF", args) {
+            print_self_reported("Image is a user mode driver", args.quiet);
+            info.set_driver(Driver {
+                user_mode: true,
+                framework: Some(framework),
+                driver_kit,
+                minimum_from_imports: None,
+            });
+        }
+        return;
+    }
+
+    let Ok(import_directory) = ImportDirectory::parse(pe) else {
+        return;
+    };
+
+    let mut kernel_modules = Vec::new();
+    let mut minimum_from_imports = None;
+    for descriptor in import_directory.descriptors {
+        let Some(module) = descriptor.get_name(pe).ok().and_then(|a| a.as_str().ok()) else {
+            continue;
+        };
+        let module = module.to_lowercase();
+        if !KERNEL_MODULES.contains(&module.as_str()) {
+            continue;
+        }
+        kernel_modules.push(module.clone());
+
+        let Ok(imports) = descriptor.get_imports(pe) else {
+            continue;
+        };
+        for import in imports {
+            let ImportData::ImportByName(name) = import else {
+                continue;
+            };
+            let Some((_, os)) = KERNEL_ROUTINES.iter().find(|(a, _)| *a == name) else {
+                continue;
+            };
+
+            print_heuristic(
+                format!("Import of kernel routine '{name}' from '{module}' suggests at least {os}"),
+                args.quiet,
+            );
+            minimum_from_imports = minimum_from_imports.max(Some(*os));
+        }
+    }
+
+    // Native user mode programs such as autochk.exe only import from ntdll.dll.
+    if kernel_modules.is_empty() {
+        print_self_reported(
+            "Subsystem is NATIVE without kernel imports, which means this is a native user mode program",
+            args.quiet,
+        );
+        return;
+    }
+
+    print_self_reported(
+        format!(
+            "Subsystem is NATIVE and imports from {}, which means this is a kernel mode driver",
+            kernel_modules.join(", ")
+        ),
+        args.quiet,
+    );
+
+    if let (Some(minimum), Some(os)) = (minimum_from_imports, info.os()) {
+        if minimum > os {
+            print_heuristic(
+                format!("Imported kernel routines require {minimum}, which is newer than the operating system version ({os})"),
+                args.quiet,
+            );
+        }
+    }
+
+    let (framework, mut driver_kit) = match framework(pe, "KmdfLibrary", "KMDF", args) {
+        Some((framework, driver_kit)) => (Some(framework), driver_kit),
+        None => (None, None),
+    };
+
+    if framework.is_none() {
+        if kernel_modules.iter().any(|a| a == "wdfldr.sys") {
+            print_self_reported(
+                "Imports from wdfldr.sys but the WdfVersionBind data could not be found",
+                args.quiet,
+            );
+        }
+
+        let (major, minor) = (header.major_linker_version, header.minor_linker_version);
+        if let Some(kit) = driver_kit_from_linker_version(major, minor) {
+            print_heuristic(
+                format!("Linker version {major}.{minor} of a WDM driver suggests {kit}"),
+                args.quiet,
+            );
+            driver_kit = Some(kit);
+        }
+    }
+
+    info.set_driver(Driver {
+        user_mode: false,
+        framework,
+        driver_kit,
+        minimum_from_imports,
+    });
+}
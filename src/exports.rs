@@ -0,0 +1,138 @@
+use crate::cpp_symbols::versions_from_cpp_symbols;
+use crate::demangle::demangle;
+use crate::info::Info;
+use crate::read::{read_rva, read_rva_cstring, read_u16, read_u32};
+use crate::{print_heuristic, print_self_reported, Args};
+use exe::{ImageDirectoryEntry, PtrPE, PE};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const IMAGE_EXPORT_DIRECTORY_SIZE: u32 = 40;
+
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Export {
+    pub ordinal: u32,
+    pub name: Option<String>,
+    /// Exports that point into the export directory are `module.function` strings instead of code.
+    pub forwarder: Option<String>,
+}
+
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Exports {
+    pub dll_name: Option<String>,
+    pub exports: Vec<Export>,
+}
+
+fn parse_exports(pe: &PtrPE) -> Option<Exports> {
+    let directory = pe.get_data_directory(ImageDirectoryEntry::Export).ok()?;
+    let start = directory.virtual_address.0;
+    let end = start.saturating_add(directory.size);
+    let header = read_rva(pe, start, IMAGE_EXPORT_DIRECTORY_SIZE)?;
+
+    let dll_name = read_rva_cstring(pe, read_u32(header, 12)?);
+    let base = read_u32(header, 16)?;
+    let function_count = read_u32(header, 20)?;
+    let name_count = read_u32(header, 24)?;
+    let functions = read_rva(pe, read_u32(header, 28)?, function_count.checked_mul(4)?)?;
+    let names = read_rva(pe, read_u32(header, 32)?, name_count.checked_mul(4)?).unwrap_or_default();
+    let name_ordinals =
+        read_rva(pe, read_u32(header, 36)?, name_count.checked_mul(2)?).unwrap_or_default();
+
+    let mut function_names = BTreeMap::new();
+    for i in 0..names.len() / 4 {
+        let (Some(name), Some(index)) = (read_u32(names, i * 4), read_u16(name_ordinals, i * 2))
+        else {
+            continue;
+        };
+        if let Some(name) = read_rva_cstring(pe, name) {
+            function_names.insert(index as u32, name);
+        }
+    }
+
+    let mut exports = Vec::new();
+    for index in 0..function_count {
+        let Some(address) = read_u32(functions, index as usize * 4) else {
+            continue;
+        };
+        // Unused ordinals in the middle of the table have no address.
+        if address == 0 {
+            continue;
+        }
+
+        let forwarder = (start..end)
+            .contains(&address)
+            .then(|| read_rva_cstring(pe, address))
+            .flatten();
+        exports.push(Export {
+            ordinal: base.wrapping_add(index),
+            name: function_names.remove(&index),
+            forwarder,
+        });
+    }
+
+    Some(Exports { dll_name, exports })
+}
+
+pub fn versions_from_exports(pe: &PtrPE, file_name: &str, info: &mut Info, args: &Args) {
+    let Some(exports) = parse_exports(pe) else {
+        return;
+    };
+
+    let by_ordinal = exports.exports.iter().filter(|a| a.name.is_none()).count();
+    print_self_reported(
+        format!(
+            "Export directory has {} exports, {by_ordinal} of them only by ordinal",
+            exports.exports.len()
+        ),
+        args.quiet,
+    );
+
+    if let Some(dll_name) = &exports.dll_name {
+        let file_name = Path::new(file_name)
+            .file_name()
+            .map(|a| a.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if dll_name.eq_ignore_ascii_case(&file_name) {
+            print_self_reported(
+                format!("Export directory names the DLL '{dll_name}'"),
+                args.quiet,
+            );
+        } else {
+            print_heuristic(
+                format!("Export directory names the DLL '{dll_name}', which differs from the file name '{file_name}', so the file was likely renamed"),
+                args.quiet,
+            );
+        }
+    }
+
+    // Forwarders are summarized by module, system DLLs can have hundreds of them.
+    let mut forwarded = BTreeMap::<String, usize>::new();
+    for forwarder in exports.exports.iter().filter_map(|a| a.forwarder.as_ref()) {
+        let module = forwarder.split('.').next().unwrap_or_default();
+        *forwarded.entry(module.to_string()).or_default() += 1;
+    }
+    for (module, amount) in forwarded {
+        print_self_reported(
+            format!("{amount} of the exports are forwarded to '{module}'"),
+            args.quiet,
+        );
+    }
+
+    let symbols = exports
+        .exports
+        .iter()
+        .filter_map(|a| a.name.as_deref())
+        .filter(|a| a.starts_with('?'))
+        .filter_map(demangle)
+        .collect::<Vec<_>>();
+    if let Some(symbol) = symbols.first() {
+        info.set_cpp(true);
+        print_heuristic(
+            format!("Exported symbol '{symbol}' suggests C++ was used"),
+            args.quiet,
+        );
+    }
+    versions_from_cpp_symbols(&symbols, "Exported", info, args);
+
+    info.set_exports(exports);
+}
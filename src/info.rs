@@ -1,32 +1,80 @@
+use crate::authenticode::Signature;
+use crate::authentihash::Authentihash;
 use crate::compiler_version::CompilerVersion;
+use crate::dotnet::DotNet;
+use crate::driver::Driver;
+use crate::exports::Exports;
+use crate::likely_incorrect::LikelyIncorrect;
+use crate::machine::{HybridCode, Machine};
+use crate::manifest::Manifest;
+use crate::side_by_side::CrtBuild;
 use crate::standard::CppStandard;
+use crate::toolchain::Toolchain;
+use crate::version_info::VersionInfo;
 use crate::versions_for_operating_system::OperatingSystem;
+use crate::visual_basic::VisualBasic;
 use chrono::{DateTime, Utc};
 use std::collections::BTreeSet;
 
 #[derive(Debug, Ord, Clone, PartialOrd, Eq, PartialEq, Hash)]
 pub struct Info {
     compiler_version: BTreeSet<CompilerVersion>,
+    toolchains: BTreeSet<Toolchain>,
     linker_version: Option<CompilerVersion>,
     standard: Option<CppStandard>,
-    timestamp: DateTime<Utc>,
+    timestamp: Option<DateTime<Utc>>,
+    timeline: BTreeSet<(DateTime<Utc>, String)>,
+    era: Option<i32>,
     is_cpp: bool,
+    is_fortran: bool,
     operating_system: Option<OperatingSystem>,
     subsystem: Option<OperatingSystem>,
-    information_likely_incorrect: bool,
+    likely_incorrect: BTreeSet<LikelyIncorrect>,
+    dotnet: Option<DotNet>,
+    visual_basic: Option<VisualBasic>,
+    driver: Option<Driver>,
+    efi_subsystem: Option<&'static str>,
+    machine: Option<Machine>,
+    hybrid_code: Option<HybridCode>,
+    manifest: Option<Manifest>,
+    crt_build: Option<CrtBuild>,
+    version_info: Option<VersionInfo>,
+    debug_build: bool,
+    signature: Option<Signature>,
+    authentihash: Option<Authentihash>,
+    exports: Option<Exports>,
+    bound_after: Option<DateTime<Utc>>,
 }
 
 impl Info {
-    pub fn new(timestamp: DateTime<Utc>) -> Self {
+    pub fn new(timestamp: Option<DateTime<Utc>>) -> Self {
         Self {
             compiler_version: Default::default(),
+            toolchains: Default::default(),
             linker_version: None,
             standard: None,
             timestamp,
+            timeline: Default::default(),
+            era: None,
             is_cpp: false,
+            is_fortran: false,
             operating_system: None,
             subsystem: None,
-            information_likely_incorrect: false,
+            likely_incorrect: Default::default(),
+            dotnet: None,
+            visual_basic: None,
+            driver: None,
+            efi_subsystem: None,
+            machine: None,
+            hybrid_code: None,
+            manifest: None,
+            crt_build: None,
+            version_info: None,
+            debug_build: false,
+            signature: None,
+            authentihash: None,
+            exports: None,
+            bound_after: None,
         }
     }
 
@@ -34,22 +82,60 @@ impl Info {
         self.compiler_version.iter().cloned().collect()
     }
 
+    pub fn toolchains(&self) -> Vec<Toolchain> {
+        self.toolchains.iter().cloned().collect()
+    }
+
+    pub fn add_toolchain(&mut self, toolchain: Toolchain) {
+        self.toolchains.insert(toolchain);
+    }
+
     pub fn linker_version(&self) -> Option<CompilerVersion> {
         self.linker_version
     }
 
-    pub fn set_likely_incorrect(&mut self) {
-        self.information_likely_incorrect = true;
+    pub fn set_likely_incorrect(&mut self, reason: LikelyIncorrect) {
+        self.likely_incorrect.insert(reason);
     }
 
     pub fn likely_correct(&self) -> bool {
-        !self.information_likely_incorrect
+        self.likely_incorrect.is_empty()
+    }
+
+    pub fn likely_incorrect_reasons(&self) -> Vec<LikelyIncorrect> {
+        self.likely_incorrect.iter().cloned().collect()
     }
 
-    pub fn timestamp(&self) -> DateTime<Utc> {
+    pub fn timestamp(&self) -> Option<DateTime<Utc>> {
         self.timestamp
     }
 
+    pub fn set_timestamp(&mut self, timestamp: DateTime<Utc>) {
+        self.timestamp = Some(timestamp);
+    }
+
+    pub fn timeline(&self) -> Vec<(DateTime<Utc>, String)> {
+        self.timeline.iter().cloned().collect()
+    }
+
+    pub fn add_to_timeline(&mut self, date: DateTime<Utc>, source: String) {
+        self.timeline.insert((date, source));
+    }
+
+    /// Approximate year of compilation for executables without a timestamp.
+    pub fn era(&self) -> Option<i32> {
+        self.era
+    }
+
+    pub fn set_era(&mut self, year: i32) {
+        self.era = Some(year);
+    }
+
+    /// Whether something released at `date` could have been used, which is always the case without a timestamp.
+    pub fn possible_at_timestamp(&self, date: DateTime<Utc>) -> bool {
+        self.timestamp.is_none_or(|timestamp| timestamp >= date)
+    }
+
     pub fn is_cpp(&self) -> bool {
         self.is_cpp
     }
@@ -60,6 +146,16 @@ impl Info {
         }
     }
 
+    pub fn is_fortran(&self) -> bool {
+        self.is_fortran
+    }
+
+    pub fn set_fortran(&mut self, is_fortran: bool) {
+        if is_fortran {
+            self.is_fortran = true;
+        }
+    }
+
     pub fn os(&self) -> Option<OperatingSystem> {
         self.operating_system
     }
@@ -96,6 +192,14 @@ impl Info {
         self.insert_single_compiler_version(version);
     }
 
+    pub fn exclude_compiler_versions_before(&mut self, version: CompilerVersion) {
+        self.compiler_version.retain(|a| *a >= version);
+    }
+
+    pub fn exclude_compiler_versions_after(&mut self, version: CompilerVersion) {
+        self.compiler_version.retain(|a| *a <= version);
+    }
+
     pub fn insert_single_compiler_version(&mut self, version: CompilerVersion) {
         self.compiler_version.insert(version);
     }
@@ -106,18 +210,130 @@ impl Info {
         }
     }
 
+    pub fn dotnet(&self) -> Option<&DotNet> {
+        self.dotnet.as_ref()
+    }
+
+    pub fn set_dotnet(&mut self, dotnet: DotNet) {
+        self.dotnet = Some(dotnet);
+    }
+
+    pub fn visual_basic(&self) -> Option<&VisualBasic> {
+        self.visual_basic.as_ref()
+    }
+
+    pub fn set_visual_basic(&mut self, visual_basic: VisualBasic) {
+        self.visual_basic = Some(visual_basic);
+    }
+
+    pub fn driver(&self) -> Option<&Driver> {
+        self.driver.as_ref()
+    }
+
+    pub fn set_driver(&mut self, driver: Driver) {
+        self.driver = Some(driver);
+    }
+
+    pub fn efi_subsystem(&self) -> Option<&'static str> {
+        self.efi_subsystem
+    }
+
+    pub fn set_efi_subsystem(&mut self, subsystem: &'static str) {
+        self.efi_subsystem = Some(subsystem);
+    }
+
+    pub fn machine(&self) -> Option<Machine> {
+        self.machine
+    }
+
+    pub fn set_machine(&mut self, machine: Machine) {
+        self.machine = Some(machine);
+    }
+
+    pub fn hybrid_code(&self) -> Option<HybridCode> {
+        self.hybrid_code
+    }
+
+    pub fn set_hybrid_code(&mut self, code: HybridCode) {
+        self.hybrid_code = Some(code);
+    }
+
+    pub fn manifest(&self) -> Option<&Manifest> {
+        self.manifest.as_ref()
+    }
+
+    pub fn set_manifest(&mut self, manifest: Manifest) {
+        self.manifest = Some(manifest);
+    }
+
+    pub fn crt_build(&self) -> Option<CrtBuild> {
+        self.crt_build
+    }
+
+    pub fn set_crt_build(&mut self, build: CrtBuild) {
+        self.crt_build = Some(build);
+    }
+
+    pub fn version_info(&self) -> Option<&VersionInfo> {
+        self.version_info.as_ref()
+    }
+
+    pub fn set_version_info(&mut self, version_info: VersionInfo) {
+        self.version_info = Some(version_info);
+    }
+
+    pub fn is_debug_build(&self) -> bool {
+        self.debug_build
+    }
+
+    pub fn set_debug_build(&mut self) {
+        self.debug_build = true;
+    }
+
+    pub fn signature(&self) -> Option<&Signature> {
+        self.signature.as_ref()
+    }
+
+    pub fn set_signature(&mut self, signature: Signature) {
+        self.signature = Some(signature);
+    }
+
+    pub fn authentihash(&self) -> Option<&Authentihash> {
+        self.authentihash.as_ref()
+    }
+
+    pub fn set_authentihash(&mut self, authentihash: Authentihash) {
+        self.authentihash = Some(authentihash);
+    }
+
+    pub fn exports(&self) -> Option<&Exports> {
+        self.exports.as_ref()
+    }
+
+    pub fn set_exports(&mut self, exports: Exports) {
+        self.exports = Some(exports);
+    }
+
+    pub fn bound_after(&self) -> Option<DateTime<Utc>> {
+        self.bound_after
+    }
+
+    pub fn set_bound_after(&mut self, date: DateTime<Utc>) {
+        self.bound_after = Some(date);
+    }
+
     pub fn minimum_operating_system(&self) -> Option<OperatingSystem> {
-        let Some(os) = self.os() else {
-            return None;
-        };
-        let Some(subsystem) = self.subsystem else {
-            return None;
+        let from_headers = match (self.os(), self.subsystem) {
+            (Some(os), Some(subsystem)) if os == subsystem => Some(os),
+            _ => None,
         };
 
-        if os == subsystem {
-            return Some(os);
-        }
+        // The machine type only says something about desktop Windows.
+        let from_machine = self
+            .machine
+            .filter(|_| self.efi_subsystem.is_none())
+            .and_then(|a| a.minimum_operating_system());
 
-        None
+        from_headers.max(from_machine)
     }
 }
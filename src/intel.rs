@@ -0,0 +1,124 @@
+use crate::compiler_version::date_to_datetime;
+use crate::info::Info;
+use crate::toolchain::Toolchain;
+use crate::{print_heuristic, Args};
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+enum Language {
+    Cpp,
+    Fortran,
+    Either,
+}
+
+fn runtime_language(module: &str) -> Option<Language> {
+    Some(match module {
+        "libifcoremd.dll" | "libifcorertd.dll" | "libifcoremdd.dll" | "libifportmd.dll"
+        | "libicaf.dll" => Language::Fortran,
+        "libmmd.dll" | "libmmdd.dll" | "svml_dispmd.dll" | "libiomp5md.dll" | "libguide40.dll"
+        | "libirngmd.dll" => Language::Either,
+        module if module.starts_with("sycl") && module.ends_with(".dll") => Language::Cpp,
+        _ => {
+            return None;
+        }
+    })
+}
+
+/// First oneAPI release, which introduced ICX and IFX.
+fn one_api_release() -> DateTime<Utc> {
+    date_to_datetime(2020, 12, 8)
+}
+
+/// oneAPI 2024.0 removed the classic C++ compiler.
+fn icl_removal() -> DateTime<Utc> {
+    date_to_datetime(2023, 11, 20)
+}
+
+/// oneAPI 2025.0 removed the classic Fortran compiler.
+fn ifort_removal() -> DateTime<Utc> {
+    date_to_datetime(2024, 11, 18)
+}
+
+fn candidates(language: Language, module: &str, info: &Info) -> Vec<Toolchain> {
+    let mut toolchains = Vec::new();
+
+    // libguide40.dll was replaced by libiomp5md.dll before oneAPI existed.
+    let one_api_possible =
+        info.possible_at_timestamp(one_api_release()) && module != "libguide40.dll";
+    // SYCL is only supported by the oneAPI compilers.
+    let classic_possible = !module.starts_with("sycl");
+
+    if matches!(language, Language::Cpp | Language::Either) {
+        if classic_possible && info.timestamp().is_none_or(|a| a < icl_removal()) {
+            toolchains.push(Toolchain::IntelCppClassic);
+        }
+        if one_api_possible {
+            toolchains.push(Toolchain::IntelCppOneApi);
+        }
+    }
+
+    if matches!(language, Language::Fortran | Language::Either) {
+        if classic_possible && info.timestamp().is_none_or(|a| a < ifort_removal()) {
+            toolchains.push(Toolchain::IntelFortranClassic);
+        }
+        if one_api_possible {
+            toolchains.push(Toolchain::IntelFortranOneApi);
+        }
+    }
+
+    toolchains
+}
+
+pub fn versions_from_intel_runtimes(modules: &[String], info: &mut Info, args: &Args) {
+    let runtimes = modules
+        .iter()
+        .filter_map(|a| {
+            let module = a.to_lowercase();
+            runtime_language(&module).map(|language| (module, language))
+        })
+        .collect::<Vec<_>>();
+
+    let fortran = runtimes.iter().any(|(_, a)| *a == Language::Fortran);
+    let cpp = runtimes.iter().any(|(_, a)| *a == Language::Cpp);
+
+    for (module, language) in &runtimes {
+        // Shared math and OpenMP runtimes only say something about the language when nothing else does.
+        let language = match language {
+            Language::Either if fortran && !cpp => Language::Fortran,
+            Language::Either if cpp && !fortran => Language::Cpp,
+            language => *language,
+        };
+
+        let toolchains = candidates(language, module, info);
+        if toolchains.is_empty() {
+            continue;
+        }
+
+        let names = toolchains
+            .iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let names = if toolchains.len() == 1 {
+            names
+        } else {
+            format!("one of {names}")
+        };
+        print_heuristic(
+            format!("Links against {module} which suggests compiler is {names}"),
+            args.quiet,
+        );
+
+        for toolchain in toolchains {
+            info.add_toolchain(toolchain);
+        }
+    }
+
+    if fortran {
+        print_heuristic(
+            "Links against the Intel Fortran runtime which suggests Fortran was used",
+            args.quiet,
+        );
+        info.set_fortran(true);
+    }
+}
@@ -0,0 +1,39 @@
+use std::fmt::Formatter;
+
+/// Why the information in an image is likely incorrect.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum LikelyIncorrect {
+    ZeroTimestamp,
+    Malformed,
+    ReleasedAfterTimestamp,
+    CopyrightAfterTimestamp,
+    SignedBeforeTimestamp,
+    ModifiedAfterSigning,
+    ChecksumMismatch,
+    TimestampsDisagree,
+    OperatingSystemMismatch,
+    UnsupportedMachine,
+}
+
+impl std::fmt::Display for LikelyIncorrect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LikelyIncorrect::ZeroTimestamp => "Timestamp is zero",
+            LikelyIncorrect::Malformed => "Headers could not be parsed",
+            LikelyIncorrect::ReleasedAfterTimestamp => {
+                "Uses something released after the timestamp"
+            }
+            LikelyIncorrect::CopyrightAfterTimestamp => "Copyright year is after the timestamp",
+            LikelyIncorrect::SignedBeforeTimestamp => "Signing time is before the timestamp",
+            LikelyIncorrect::ModifiedAfterSigning => "Image was modified after signing",
+            LikelyIncorrect::ChecksumMismatch => {
+                "CheckSum does not match, so the image was patched after linking"
+            }
+            LikelyIncorrect::TimestampsDisagree => "Timestamps in the image are years apart",
+            LikelyIncorrect::OperatingSystemMismatch => {
+                "Operating system and subsystem versions disagree"
+            }
+            LikelyIncorrect::UnsupportedMachine => "Versions do not support the machine type",
+        })
+    }
+}
@@ -1,6 +1,9 @@
 use crate::compiler_version::CompilerVersion;
 use crate::info::Info;
+use crate::minor_toolchains::{is_minor_toolchain, toolchain_for_linker_version};
 use crate::optional_header::OptionalHeader;
+use crate::toolchain::Toolchain;
+use crate::windows_ce::{is_windows_ce, versions_from_windows_ce_linker};
 use crate::{print_self_reported, Args};
 
 pub fn versions_from_linker_version(
@@ -12,7 +15,7 @@ pub fn versions_from_linker_version(
     let major = optional_header.major_linker_version;
 
     let one = |cv: CompilerVersion, info: &mut Info, major: u8, minor: u8| {
-        let validity = if cv.release_date() > info.timestamp() {
+        let validity = if !info.possible_at_timestamp(cv.release_date()) {
             "which is not possible with the timestamp"
         } else {
             "which is possible with the timestamp"
@@ -25,7 +28,25 @@ pub fn versions_from_linker_version(
         info.set_linker_version(cv);
     };
 
+    // The signatures of minor toolchains already identified the linker, so it is not a Microsoft one.
+    if let Some(toolchain) = toolchain_for_linker_version(info, major, minor) {
+        print_self_reported(
+            format!("Linker version is {major}.{minor}, which matches the linker of {toolchain}"),
+            args.quiet,
+        );
+        return;
+    }
+
+    if is_windows_ce(optional_header) {
+        versions_from_windows_ce_linker(major, minor, info, args);
+        return;
+    }
+
     match (major, minor) {
+        (0, value) if info.toolchains().contains(&Toolchain::EdkGenFw) => print_self_reported(
+            format!("Linker version is 0.{value}, which is expected for images converted by GenFw"),
+            args.quiet,
+        ),
         (0, value) => print_self_reported(
             format!("Linker version is known invalid: 0.{value}"),
             args.quiet,
@@ -49,10 +70,17 @@ pub fn versions_from_linker_version(
         (14, 20) => one(CompilerVersion::VisualStudio2019, info, major, minor),
         (14, 21..) => one(CompilerVersion::VisualStudio2022, info, major, minor),
         _ => {
-            print_self_reported(
-                format!("Linker version is unknown, possibly invalid: {major}.{minor}"),
-                args.quiet,
-            );
+            if let Some(toolchain) = info.toolchains().into_iter().find(is_minor_toolchain) {
+                print_self_reported(
+                    format!("Linker version is {major}.{minor}, which is not a Microsoft linker but possible for {toolchain}"),
+                    args.quiet,
+                );
+            } else {
+                print_self_reported(
+                    format!("Linker version is unknown, possibly invalid: {major}.{minor}"),
+                    args.quiet,
+                );
+            }
         }
     }
 }
@@ -0,0 +1,431 @@
+use crate::compiler_version::CompilerVersion;
+use crate::info::Info;
+use crate::likely_incorrect::LikelyIncorrect;
+use crate::minor_toolchains::is_minor_toolchain;
+use crate::read::{read_rva, read_u32, read_u64};
+use crate::toolchain::Toolchain;
+use crate::versions_for_operating_system::OperatingSystem;
+use crate::{print_heuristic, print_self_reported, Args};
+use exe::{ImageDirectoryEntry, PtrPE, PE};
+use std::fmt::Formatter;
+
+const IMAGE_FILE_MACHINE_I386: u16 = 0x014C;
+const IMAGE_FILE_MACHINE_R3000: u16 = 0x0162;
+const IMAGE_FILE_MACHINE_R4000: u16 = 0x0166;
+const IMAGE_FILE_MACHINE_R10000: u16 = 0x0168;
+const IMAGE_FILE_MACHINE_WCEMIPSV2: u16 = 0x0169;
+const IMAGE_FILE_MACHINE_ALPHA: u16 = 0x0184;
+const IMAGE_FILE_MACHINE_SH3: u16 = 0x01A2;
+const IMAGE_FILE_MACHINE_SH3DSP: u16 = 0x01A3;
+const IMAGE_FILE_MACHINE_SH3E: u16 = 0x01A4;
+const IMAGE_FILE_MACHINE_SH4: u16 = 0x01A6;
+const IMAGE_FILE_MACHINE_SH5: u16 = 0x01A8;
+const IMAGE_FILE_MACHINE_ARM: u16 = 0x01C0;
+const IMAGE_FILE_MACHINE_THUMB: u16 = 0x01C2;
+const IMAGE_FILE_MACHINE_ARMNT: u16 = 0x01C4;
+const IMAGE_FILE_MACHINE_AM33: u16 = 0x01D3;
+const IMAGE_FILE_MACHINE_POWERPC: u16 = 0x01F0;
+const IMAGE_FILE_MACHINE_POWERPCFP: u16 = 0x01F1;
+const IMAGE_FILE_MACHINE_IA64: u16 = 0x0200;
+const IMAGE_FILE_MACHINE_MIPS16: u16 = 0x0266;
+const IMAGE_FILE_MACHINE_ALPHA64: u16 = 0x0284;
+const IMAGE_FILE_MACHINE_MIPSFPU: u16 = 0x0366;
+const IMAGE_FILE_MACHINE_MIPSFPU16: u16 = 0x0466;
+const IMAGE_FILE_MACHINE_TRICORE: u16 = 0x0520;
+const IMAGE_FILE_MACHINE_CEF: u16 = 0x0CEF;
+const IMAGE_FILE_MACHINE_EBC: u16 = 0x0EBC;
+const IMAGE_FILE_MACHINE_RISCV32: u16 = 0x5032;
+const IMAGE_FILE_MACHINE_RISCV64: u16 = 0x5064;
+const IMAGE_FILE_MACHINE_RISCV128: u16 = 0x5128;
+const IMAGE_FILE_MACHINE_LOONGARCH32: u16 = 0x6232;
+const IMAGE_FILE_MACHINE_LOONGARCH64: u16 = 0x6264;
+const IMAGE_FILE_MACHINE_AMD64: u16 = 0x8664;
+const IMAGE_FILE_MACHINE_M32R: u16 = 0x9041;
+const IMAGE_FILE_MACHINE_ARM64: u16 = 0xAA64;
+const IMAGE_FILE_MACHINE_CEE: u16 = 0xC0EE;
+
+/// Offset of `CHPEMetadataPointer` in `IMAGE_LOAD_CONFIG_DIRECTORY64`.
+const CHPE_METADATA_POINTER: usize = 0xC8;
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Machine {
+    X86,
+    MipsR3000,
+    Mips,
+    MipsR10000,
+    /// MIPS II for Windows CE.
+    MipsWceV2,
+    Mips16,
+    MipsFpu,
+    Mips16Fpu,
+    Alpha,
+    Alpha64,
+    Sh3,
+    Sh3Dsp,
+    Sh3E,
+    Sh4,
+    Sh5,
+    /// ARM in ARM mode, which only Windows CE ran.
+    ArmCe,
+    Thumb,
+    Am33,
+    PowerPc,
+    /// PowerPC with floating point support.
+    PowerPcFp,
+    Ia64,
+    TriCore,
+    M32R,
+    /// EFI byte code, only run by UEFI firmware like the RISC-V and LoongArch machines.
+    Ebc,
+    RiscV32,
+    RiscV64,
+    RiscV128,
+    LoongArch32,
+    LoongArch64,
+    /// Common Executable Format.
+    Cef,
+    /// Managed code.
+    Cee,
+    X64,
+    Arm,
+    Arm64,
+    /// x64 image with ARM64EC code, described by the CHPE metadata.
+    Arm64Ec,
+    /// ARM64 image with both native ARM64 and ARM64EC code, described by the CHPE metadata.
+    Arm64X,
+    Other(u16),
+}
+
+impl std::fmt::Display for Machine {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Machine::X86 => f.write_str("x86"),
+            Machine::MipsR3000 => f.write_str("MIPS R3000"),
+            Machine::Mips => f.write_str("MIPS R4000"),
+            Machine::MipsR10000 => f.write_str("MIPS R10000"),
+            Machine::MipsWceV2 => f.write_str("MIPS II (Windows CE)"),
+            Machine::Mips16 => f.write_str("MIPS16"),
+            Machine::MipsFpu => f.write_str("MIPS with FPU"),
+            Machine::Mips16Fpu => f.write_str("MIPS16 with FPU"),
+            Machine::Alpha => f.write_str("Alpha AXP"),
+            Machine::Alpha64 => f.write_str("Alpha AXP 64-bit"),
+            Machine::Sh3 => f.write_str("Hitachi SH-3"),
+            Machine::Sh3Dsp => f.write_str("Hitachi SH-3 DSP"),
+            Machine::Sh3E => f.write_str("Hitachi SH-3E"),
+            Machine::Sh4 => f.write_str("Hitachi SH-4"),
+            Machine::Sh5 => f.write_str("Hitachi SH-5"),
+            Machine::ArmCe => f.write_str("ARM"),
+            Machine::Thumb => f.write_str("ARM (Thumb)"),
+            Machine::Am33 => f.write_str("Matsushita AM33"),
+            Machine::PowerPc => f.write_str("PowerPC"),
+            Machine::PowerPcFp => f.write_str("PowerPC with FPU"),
+            Machine::Ia64 => f.write_str("IA-64 (Itanium)"),
+            Machine::TriCore => f.write_str("Infineon TriCore"),
+            Machine::M32R => f.write_str("Mitsubishi M32R"),
+            Machine::Ebc => f.write_str("EFI byte code"),
+            Machine::RiscV32 => f.write_str("RISC-V 32-bit"),
+            Machine::RiscV64 => f.write_str("RISC-V 64-bit"),
+            Machine::RiscV128 => f.write_str("RISC-V 128-bit"),
+            Machine::LoongArch32 => f.write_str("LoongArch 32-bit"),
+            Machine::LoongArch64 => f.write_str("LoongArch 64-bit"),
+            Machine::Cef => f.write_str("CEF"),
+            Machine::Cee => f.write_str("CEE (managed code)"),
+            Machine::X64 => f.write_str("x64"),
+            Machine::Arm => f.write_str("ARM (Thumb-2)"),
+            Machine::Arm64 => f.write_str("ARM64"),
+            Machine::Arm64Ec => f.write_str("ARM64EC"),
+            Machine::Arm64X => f.write_str("ARM64X"),
+            Machine::Other(machine) => write!(f, "unknown (0x{machine:04X})"),
+        }
+    }
+}
+
+impl Machine {
+    /// Machines that Windows CE ran on, but no desktop version of Windows.
+    pub fn is_windows_ce_only(&self) -> bool {
+        matches!(
+            self,
+            Machine::MipsWceV2
+                | Machine::Mips16
+                | Machine::MipsFpu
+                | Machine::Mips16Fpu
+                | Machine::Sh3
+                | Machine::Sh3Dsp
+                | Machine::Sh3E
+                | Machine::Sh4
+                | Machine::Sh5
+                | Machine::ArmCe
+                | Machine::Thumb
+                | Machine::Am33
+                | Machine::M32R
+        )
+    }
+
+    /// First Visual Studio release that could target the machine.
+    pub fn minimum_compiler_version(&self) -> Option<CompilerVersion> {
+        Some(match self {
+            // Itanium compilers first shipped in the Platform SDK, built from the Visual Studio .NET 2002 sources.
+            Machine::Ia64 => CompilerVersion::VisualStudioDotNet2002,
+            Machine::X64 => CompilerVersion::VisualStudio2005,
+            Machine::Arm => CompilerVersion::VisualStudio2012,
+            // Only Visual Studio 2017 15.9 could build ARM64 desktop applications.
+            Machine::Arm64 => CompilerVersion::VisualStudio2017,
+            Machine::Arm64Ec | Machine::Arm64X => CompilerVersion::VisualStudio2022,
+            _ => {
+                return None;
+            }
+        })
+    }
+
+    /// Last Visual Studio release that could target the machine.
+    pub fn maximum_compiler_version(&self) -> Option<CompilerVersion> {
+        Some(match self {
+            Machine::Mips | Machine::Alpha | Machine::PowerPc | Machine::PowerPcFp => {
+                CompilerVersion::VisualCPP6_0
+            }
+            // Visual Studio 2010 is the last release with an Itanium compiler.
+            Machine::Ia64 => CompilerVersion::VisualStudio2010,
+            _ => {
+                return None;
+            }
+        })
+    }
+
+    /// First version of Windows that could run the machine.
+    pub fn minimum_operating_system(&self) -> Option<OperatingSystem> {
+        Some(match self {
+            Machine::Mips | Machine::Alpha => OperatingSystem::WindowsNt3_1,
+            Machine::PowerPc | Machine::PowerPcFp => OperatingSystem::WindowsNt3_51,
+            Machine::Ia64 => OperatingSystem::WindowsXP,
+            Machine::X64 => OperatingSystem::WindowsServer2003,
+            Machine::Arm => OperatingSystem::Windows8,
+            // ARM64EC needs Windows 11, which reports itself as Windows 10.
+            Machine::Arm64 | Machine::Arm64Ec | Machine::Arm64X => OperatingSystem::Windows10,
+            _ => {
+                return None;
+            }
+        })
+    }
+
+    /// Last version of Windows that could run the machine.
+    pub fn maximum_operating_system(&self) -> Option<OperatingSystem> {
+        Some(match self {
+            // Windows 2000 dropped Alpha support shortly before its release.
+            Machine::Mips | Machine::Alpha | Machine::PowerPc | Machine::PowerPcFp => {
+                OperatingSystem::WindowsNt4_0
+            }
+            // Windows Server 2008 R2 is the last release for Itanium.
+            Machine::Ia64 => OperatingSystem::Windows7,
+            _ => {
+                return None;
+            }
+        })
+    }
+}
+
+/// Amount of code in bytes of each kind in a hybrid image.
+#[derive(Debug, Copy, Clone, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct HybridCode {
+    pub arm64: u32,
+    pub arm64ec: u32,
+    pub x64: u32,
+}
+
+/// Reads the code map from `IMAGE_ARM64EC_METADATA`, if the load config points to one.
+fn hybrid_code(pe: &PtrPE) -> Option<HybridCode> {
+    let directory = pe
+        .get_data_directory(ImageDirectoryEntry::LoadConfig)
+        .ok()?;
+    let load_config = read_rva(pe, directory.virtual_address.0, directory.size)?;
+    if (read_u32(load_config, 0)? as usize) < CHPE_METADATA_POINTER + 8 {
+        return None;
+    }
+
+    let metadata = read_u64(load_config, CHPE_METADATA_POINTER)?;
+    if metadata == 0 {
+        return None;
+    }
+    let metadata_rva = metadata.checked_sub(pe.get_image_base().ok()?)? as u32;
+    let metadata = read_rva(pe, metadata_rva, 12)?;
+
+    let code_map = read_u32(metadata, 4)?;
+    let code_map_count = read_u32(metadata, 8)?;
+    let code_map = read_rva(pe, code_map, code_map_count.checked_mul(8)?)?;
+
+    let mut code = HybridCode::default();
+    for entry in code_map.chunks_exact(8) {
+        let start = read_u32(entry, 0)?;
+        let length = read_u32(entry, 4)?;
+        match start & 3 {
+            0 => code.arm64 = code.arm64.saturating_add(length),
+            1 => code.arm64ec = code.arm64ec.saturating_add(length),
+            2 => code.x64 = code.x64.saturating_add(length),
+            _ => {}
+        }
+    }
+
+    Some(code)
+}
+
+pub fn versions_from_machine(pe: &PtrPE, machine: u16, info: &mut Info, args: &Args) {
+    let hybrid = match machine {
+        IMAGE_FILE_MACHINE_AMD64 | IMAGE_FILE_MACHINE_ARM64 => hybrid_code(pe),
+        _ => None,
+    };
+    let machine = match (machine, hybrid) {
+        (IMAGE_FILE_MACHINE_I386, _) => Machine::X86,
+        (IMAGE_FILE_MACHINE_R3000, _) => Machine::MipsR3000,
+        (IMAGE_FILE_MACHINE_R4000, _) => Machine::Mips,
+        (IMAGE_FILE_MACHINE_R10000, _) => Machine::MipsR10000,
+        (IMAGE_FILE_MACHINE_WCEMIPSV2, _) => Machine::MipsWceV2,
+        (IMAGE_FILE_MACHINE_MIPS16, _) => Machine::Mips16,
+        (IMAGE_FILE_MACHINE_MIPSFPU, _) => Machine::MipsFpu,
+        (IMAGE_FILE_MACHINE_MIPSFPU16, _) => Machine::Mips16Fpu,
+        (IMAGE_FILE_MACHINE_ALPHA, _) => Machine::Alpha,
+        (IMAGE_FILE_MACHINE_ALPHA64, _) => Machine::Alpha64,
+        (IMAGE_FILE_MACHINE_SH3, _) => Machine::Sh3,
+        (IMAGE_FILE_MACHINE_SH3DSP, _) => Machine::Sh3Dsp,
+        (IMAGE_FILE_MACHINE_SH3E, _) => Machine::Sh3E,
+        (IMAGE_FILE_MACHINE_SH4, _) => Machine::Sh4,
+        (IMAGE_FILE_MACHINE_SH5, _) => Machine::Sh5,
+        (IMAGE_FILE_MACHINE_ARM, _) => Machine::ArmCe,
+        (IMAGE_FILE_MACHINE_THUMB, _) => Machine::Thumb,
+        (IMAGE_FILE_MACHINE_AM33, _) => Machine::Am33,
+        (IMAGE_FILE_MACHINE_POWERPC, _) => Machine::PowerPc,
+        (IMAGE_FILE_MACHINE_POWERPCFP, _) => Machine::PowerPcFp,
+        (IMAGE_FILE_MACHINE_IA64, _) => Machine::Ia64,
+        (IMAGE_FILE_MACHINE_TRICORE, _) => Machine::TriCore,
+        (IMAGE_FILE_MACHINE_M32R, _) => Machine::M32R,
+        (IMAGE_FILE_MACHINE_EBC, _) => Machine::Ebc,
+        (IMAGE_FILE_MACHINE_RISCV32, _) => Machine::RiscV32,
+        (IMAGE_FILE_MACHINE_RISCV64, _) => Machine::RiscV64,
+        (IMAGE_FILE_MACHINE_RISCV128, _) => Machine::RiscV128,
+        (IMAGE_FILE_MACHINE_LOONGARCH32, _) => Machine::LoongArch32,
+        (IMAGE_FILE_MACHINE_LOONGARCH64, _) => Machine::LoongArch64,
+        (IMAGE_FILE_MACHINE_CEF, _) => Machine::Cef,
+        (IMAGE_FILE_MACHINE_CEE, _) => Machine::Cee,
+        (IMAGE_FILE_MACHINE_ARMNT, _) => Machine::Arm,
+        (IMAGE_FILE_MACHINE_AMD64, None) => Machine::X64,
+        (IMAGE_FILE_MACHINE_AMD64, Some(_)) => Machine::Arm64Ec,
+        (IMAGE_FILE_MACHINE_ARM64, None) => Machine::Arm64,
+        (IMAGE_FILE_MACHINE_ARM64, Some(_)) => Machine::Arm64X,
+        (machine, _) => Machine::Other(machine),
+    };
+
+    match hybrid {
+        None => print_self_reported(format!("Machine type is {machine}"), args.quiet),
+        Some(code) => {
+            print_self_reported(
+                format!("Machine type is {machine} with CHPE metadata, so this is a hybrid image"),
+                args.quiet,
+            );
+            print_self_reported(
+                format!(
+                    "CHPE code map has {} bytes of ARM64, {} bytes of ARM64EC and {} bytes of x64 code",
+                    code.arm64, code.arm64ec, code.x64
+                ),
+                args.quiet,
+            );
+            info.set_hybrid_code(code);
+        }
+    }
+
+    info.set_machine(machine);
+}
+
+/// Removes everything that could not have targeted the machine, which is only known once the other checks ran.
+pub fn constrain_to_machine(info: &mut Info, args: &Args) {
+    let Some(machine) = info.machine() else {
+        return;
+    };
+
+    // Windows CE and its smart device compilers supported MIPS, PowerPC and ARM long after Windows NT.
+    let windows_ce = machine.is_windows_ce_only()
+        || info.os().is_some_and(|a| a.is_windows_ce())
+        || info.toolchains().iter().any(|a| {
+            matches!(
+                a,
+                Toolchain::VisualStudio2005SmartDevice | Toolchain::VisualStudio2008SmartDevice
+            )
+        });
+    let (minimum, maximum) = if windows_ce {
+        (None, None)
+    } else {
+        (
+            machine.minimum_compiler_version(),
+            machine.maximum_compiler_version(),
+        )
+    };
+    let possible =
+        |a: CompilerVersion| minimum.is_none_or(|b| a >= b) && maximum.is_none_or(|b| a <= b);
+
+    if info.compiler_version().iter().any(|a| !possible(*a)) {
+        print_heuristic(
+            format!("Machine type {machine} excludes compilers that could not target it"),
+            args.quiet,
+        );
+        if let Some(minimum) = minimum {
+            info.exclude_compiler_versions_before(minimum);
+        }
+        if let Some(maximum) = maximum {
+            info.exclude_compiler_versions_after(maximum);
+        }
+    }
+
+    // Other linkers write their own versions, which only look like Microsoft linker versions.
+    let foreign_linker = info.toolchains().iter().any(|a| {
+        is_minor_toolchain(a)
+            || matches!(
+                a,
+                Toolchain::Gnu | Toolchain::Llvm | Toolchain::Borland32 | Toolchain::EdkGenFw
+            )
+    });
+
+    match info.linker_version() {
+        _ if foreign_linker => {}
+        Some(linker) if !possible(linker) => {
+            info.set_likely_incorrect(LikelyIncorrect::UnsupportedMachine);
+            print_heuristic(
+                format!("Linker version suggests {linker}, which cannot target {machine}"),
+                args.quiet,
+            );
+        }
+        // Visual Studio .NET 2002 and 2003 had no Itanium compiler, so these came from the Platform SDK.
+        Some(linker) if machine == Machine::Ia64 && linker < CompilerVersion::VisualStudio2005 => {
+            print_heuristic(
+                format!("Linker version suggests {linker}, which means the {machine} toolchain came from the Platform SDK"),
+                args.quiet,
+            );
+            info.add_toolchain(Toolchain::PlatformSdkIa64);
+        }
+        _ => {}
+    }
+
+    if let Some(minimum) = minimum {
+        if !info.possible_at_timestamp(minimum.release_date()) {
+            print_heuristic(
+                format!("Timestamp is older than {minimum}, which is the first version to target {machine}, so it is likely not a real date"),
+                args.quiet,
+            );
+        }
+    }
+
+    let Some(os) = info.os().filter(|a| !a.is_windows_ce() && !windows_ce) else {
+        return;
+    };
+    if let Some(minimum) = machine.minimum_operating_system().filter(|a| os < *a) {
+        print_heuristic(
+            format!(
+                "Operating system version suggests {os}, but {machine} needs at least {minimum}"
+            ),
+            args.quiet,
+        );
+    }
+    if let Some(maximum) = machine.maximum_operating_system().filter(|a| os > *a) {
+        info.set_likely_incorrect(LikelyIncorrect::UnsupportedMachine);
+        print_heuristic(
+            format!("Operating system version suggests {os}, but {machine} is not supported after {maximum}"),
+            args.quiet,
+        );
+    }
+}
@@ -1,21 +1,72 @@
+mod authenticode;
+mod authentihash;
+mod bound_imports;
+mod checksum;
+mod clr_metadata;
 mod compiler_version;
 mod conclusion;
+mod cpp_symbols;
+mod delay_imports;
+mod demangle;
+mod der;
 mod dll_imports;
+mod dos;
+mod dos_stub;
+mod dotnet;
+mod dotnet_native;
+mod driver;
+mod exports;
 mod info;
 mod information_source;
+mod intel;
+mod likely_incorrect;
 mod linker_version;
+mod machine;
+mod manifest;
+mod minor_toolchains;
+mod ne;
 mod optional_header;
+mod ordinals;
+mod read;
+mod resources;
+mod side_by_side;
 mod standard;
+mod timestamps;
+mod toolchain;
+mod uefi;
+mod version_info;
 mod versions_for_operating_system;
+mod visual_basic;
+mod windows_ce;
 
+use crate::authenticode::versions_from_authenticode;
+use crate::authentihash::versions_from_authentihash;
+use crate::bound_imports::versions_from_bound_imports;
+use crate::checksum::versions_from_checksum;
 use crate::compiler_version::CompilerVersion;
 use crate::conclusion::print_conclusion;
 use crate::dll_imports::versions_from_imported_dlls;
+use crate::dos::{is_pe, versions_from_dos_header};
+use crate::dos_stub::versions_from_dos_stub;
+use crate::dotnet::versions_from_clr_header;
+use crate::dotnet_native::versions_from_dotnet_host;
+use crate::driver::versions_from_driver;
+use crate::exports::versions_from_exports;
 use crate::info::Info;
 use crate::information_source::InformationSource;
+use crate::likely_incorrect::LikelyIncorrect;
 use crate::linker_version::versions_from_linker_version;
+use crate::machine::{constrain_to_machine, versions_from_machine};
+use crate::manifest::versions_from_manifest;
+use crate::minor_toolchains::versions_from_toolchain_signatures;
+use crate::ne::{ne_header_offset, versions_from_ne_header};
 use crate::optional_header::OptionalHeader;
+use crate::side_by_side::versions_from_side_by_side;
+use crate::timestamps::{is_reproducible, versions_from_timestamps, DELPHI_TIMESTAMP};
+use crate::uefi::{is_efi, versions_from_efi};
+use crate::version_info::versions_from_version_info;
 use crate::versions_for_operating_system::versions_for_operating_system;
+use crate::visual_basic::versions_from_visual_basic_header;
 use chrono::DateTime;
 use clap::Parser;
 use exe::VecPE;
@@ -49,21 +100,44 @@ pub struct Args {
     files: Vec<String>,
 }
 
-fn main() -> ExitCode {
-    let args = Args::parse();
-
-    let mut success = true;
-
-    let length = args.files.len();
-    for (i, name) in args.files.iter().enumerate() {
-        if !args.quiet {
-            println!("Reading '{name}'");
-        }
-
-        let file = std::fs::read(name).unwrap();
-        let pe = VecPE::from_disk_file(name).unwrap();
-        let parser = pe_parser::pe::parse_portable_executable(&file).unwrap();
+fn analyze_pe(name: &str, file: &[u8], args: &Args) -> Info {
+    let pe = VecPE::from_disk_file(name).unwrap();
+    let parser = pe_parser::pe::parse_portable_executable(file).unwrap();
 
+    let values = if let Some(header) = &parser.optional_header_32 {
+        Some(OptionalHeader::from_header32(header))
+    } else {
+        parser
+            .optional_header_64
+            .as_ref()
+            .map(OptionalHeader::from_header64)
+    };
+
+    // GenFw always writes a zero timestamp for EFI images, so it does not mean the image was modified.
+    let mut info = if parser.coff.time_date_stamp == 0 && values.as_ref().is_some_and(is_efi) {
+        print_self_reported(
+            "Timestamp is zero, which is expected for EFI images converted by GenFw",
+            args.quiet,
+        );
+        Info::new(None)
+    } else if is_reproducible(&pe.as_ptr_pe()) {
+        print_self_reported(
+            format!(
+                "Timestamp {:#010x} is a hash of the contents since the image has a REPRO debug directory",
+                parser.coff.time_date_stamp
+            ),
+            args.quiet,
+        );
+        Info::new(None)
+    } else if parser.coff.time_date_stamp == DELPHI_TIMESTAMP {
+        print_self_reported(
+            format!(
+                "Timestamp {DELPHI_TIMESTAMP:#010x} is the fixed value written by Delphi 4 to 2006"
+            ),
+            args.quiet,
+        );
+        Info::new(None)
+    } else {
         let timestamp_inconsistent = parser.coff.time_date_stamp == 0;
         let date = DateTime::from_timestamp(parser.coff.time_date_stamp.into(), 0).unwrap();
         let newest_possible = CompilerVersion::newest_possible(date);
@@ -78,28 +152,89 @@ fn main() -> ExitCode {
             ),
             args.quiet,
         );
-        let mut info = Info::new(date);
 
+        let mut info = Info::new(Some(date));
         if timestamp_inconsistent {
-            info.set_likely_incorrect();
+            info.set_likely_incorrect(LikelyIncorrect::ZeroTimestamp);
         }
+        info
+    };
+
+    versions_from_timestamps(
+        &pe.as_ptr_pe(),
+        parser.coff.time_date_stamp,
+        &mut info,
+        args,
+    );
+    versions_from_bound_imports(
+        &pe.as_ptr_pe(),
+        parser.coff.time_date_stamp,
+        &mut info,
+        args,
+    );
+    versions_from_machine(&pe.as_ptr_pe(), parser.coff.machine, &mut info, args);
+
+    if let Err(e) = versions_from_imported_dlls(pe.as_ptr_pe(), &mut info, args) {
+        print_self_reported(e, args.quiet);
+    }
+
+    versions_from_clr_header(&pe.as_ptr_pe(), &mut info, args);
+    versions_from_dotnet_host(&pe.as_ptr_pe(), &mut info, args);
+    versions_from_visual_basic_header(&pe.as_ptr_pe(), &mut info, args);
+    versions_from_exports(&pe.as_ptr_pe(), name, &mut info, args);
+
+    if let Some(header) = values {
+        versions_from_efi(
+            &pe.as_ptr_pe(),
+            &header,
+            parser.coff.time_date_stamp,
+            &mut info,
+            args,
+        );
+        versions_from_toolchain_signatures(&pe.as_ptr_pe(), &header, &mut info, args);
+        versions_from_linker_version(&header, &mut info, args);
+
+        versions_for_operating_system(&header, &mut info, args);
+        versions_from_manifest(&pe.as_ptr_pe(), &mut info, args);
+        versions_from_side_by_side(&mut info, args);
+        versions_from_version_info(&pe.as_ptr_pe(), &mut info, args);
+        versions_from_driver(&pe.as_ptr_pe(), &header, &mut info, args);
+        versions_from_checksum(file, &header, &mut info, args);
+    }
 
-        versions_from_imported_dlls(pe.as_ptr_pe(), &mut info, &args).unwrap();
+    versions_from_dos_stub(file, &mut info, args);
+    versions_from_authenticode(&pe.as_ptr_pe(), &mut info, args);
+    versions_from_authentihash(file, &mut info, args);
+    constrain_to_machine(&mut info, args);
 
-        let values = if let Some(header) = &parser.optional_header_32 {
-            Some(OptionalHeader::from_header32(header))
-        } else if let Some(header) = &parser.optional_header_64 {
-            Some(OptionalHeader::from_header64(header))
-        } else {
-            None
-        };
+    info
+}
 
-        if let Some(header) = values {
-            versions_from_linker_version(&header, &mut info, &args);
+fn main() -> ExitCode {
+    let args = Args::parse();
 
-            versions_for_operating_system(&header, &mut info, &args);
+    let mut success = true;
+
+    let length = args.files.len();
+    for (i, name) in args.files.iter().enumerate() {
+        if !args.quiet {
+            println!("Reading '{name}'");
         }
 
+        let file = std::fs::read(name).unwrap();
+
+        let info = if let Some(ne) = ne_header_offset(&file) {
+            let mut info = Info::new(None);
+            versions_from_ne_header(&file, ne, &mut info, &args);
+            info
+        } else if is_pe(&file) {
+            analyze_pe(name, &file, &args)
+        } else {
+            let mut info = Info::new(None);
+            versions_from_dos_header(&file, &mut info, &args);
+            info
+        };
+
         success = print_conclusion(info, &args);
 
         if i + 1 != length {
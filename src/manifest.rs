@@ -0,0 +1,253 @@
+use crate::compiler_version::date_to_datetime;
+use crate::info::Info;
+use crate::likely_incorrect::LikelyIncorrect;
+use crate::resources::resources_of_type;
+use crate::versions_for_operating_system::OperatingSystem;
+use crate::{print_heuristic, print_self_reported, Args};
+use chrono::{DateTime, Utc};
+use exe::{PtrPE, ResourceID};
+
+/// GUIDs of `supportedOS` elements, which were all introduced with Windows 7 except for the newer releases.
+const SUPPORTED_OS: &[(&str, OperatingSystem)] = &[
+    (
+        "{e2011457-1546-43c5-a5fe-008deee3d3f0}",
+        OperatingSystem::WindowsVista,
+    ),
+    (
+        "{35138b9a-5d96-4fbd-8e2d-a2440225f93a}",
+        OperatingSystem::Windows7,
+    ),
+    (
+        "{4a2f28e3-53b9-4441-ba9c-d69d4a4a6e38}",
+        OperatingSystem::Windows8,
+    ),
+    (
+        "{1f676c76-80e1-4239-95bb-83d0f6d0da78}",
+        OperatingSystem::Windows8_1,
+    ),
+    (
+        "{8e0f7a12-bfb3-4fe8-b9a5-48fd50a15a9a}",
+        OperatingSystem::Windows10,
+    ),
+];
+
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Manifest {
+    pub supported_os: Vec<OperatingSystem>,
+    pub max_version_tested: Option<String>,
+    pub execution_level: Option<String>,
+    pub dpi_awareness: Option<String>,
+    pub long_path_aware: bool,
+    /// Names and versions of the side-by-side assemblies the image depends on.
+    pub dependencies: Vec<(String, String)>,
+}
+
+/// First time a GUID could appear in a manifest, which is when an SDK with it was released.
+fn supported_os_introduced(os: OperatingSystem) -> DateTime<Utc> {
+    match os {
+        OperatingSystem::Windows8 => date_to_datetime(2012, 8, 15),
+        OperatingSystem::Windows8_1 => date_to_datetime(2013, 10, 17),
+        OperatingSystem::Windows10 => date_to_datetime(2015, 7, 29),
+        _ => date_to_datetime(2009, 10, 22),
+    }
+}
+
+/// Finds the start tags with the local name `name`, ignoring namespace prefixes.
+///
+/// Returns the tag itself and the text following it.
+fn elements<'a>(xml: &'a str, name: &str) -> Vec<(&'a str, &'a str)> {
+    let mut elements = Vec::new();
+
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+
+        let tag_name = tag
+            .split(|a: char| a.is_whitespace() || a == '/')
+            .next()
+            .unwrap_or_default();
+        let local_name = tag_name.rsplit(':').next().unwrap_or_default();
+        if local_name.eq_ignore_ascii_case(name) {
+            let text = rest.find('<').map_or(rest, |a| &rest[..a]);
+            elements.push((tag, text.trim()));
+        }
+    }
+
+    elements
+}
+
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = tag;
+    while let Some(position) = rest.find(name) {
+        let before = rest[..position].chars().next_back();
+        rest = &rest[position + name.len()..];
+        if !before.is_some_and(char::is_whitespace) {
+            continue;
+        }
+
+        let Some(value) = rest.trim_start().strip_prefix('=') else {
+            continue;
+        };
+        let value = value.trim_start();
+        let quote = value.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            continue;
+        }
+
+        let value = &value[1..];
+        return value.find(quote).map(|a| &value[..a]);
+    }
+
+    None
+}
+
+fn parse_manifest(xml: &str) -> Manifest {
+    let mut supported_os = elements(xml, "supportedOS")
+        .iter()
+        .filter_map(|(tag, _)| attribute(tag, "Id"))
+        .filter_map(|id| {
+            SUPPORTED_OS
+                .iter()
+                .find(|(guid, _)| guid.eq_ignore_ascii_case(id))
+        })
+        .map(|(_, os)| *os)
+        .collect::<Vec<_>>();
+    supported_os.sort();
+    supported_os.dedup();
+
+    let max_version_tested = elements(xml, "maxversiontested")
+        .iter()
+        .filter_map(|(tag, _)| attribute(tag, "Id"))
+        .map(|a| a.to_string())
+        .max();
+
+    let execution_level = elements(xml, "requestedExecutionLevel")
+        .first()
+        .and_then(|(tag, _)| attribute(tag, "level"))
+        .map(|a| a.to_string());
+
+    // dpiAwareness replaces dpiAware on Windows 10 1607 and newer.
+    let dpi_awareness = elements(xml, "dpiAwareness")
+        .first()
+        .or(elements(xml, "dpiAware").first())
+        .map(|(_, text)| text.to_string())
+        .filter(|a| !a.is_empty());
+
+    let long_path_aware = elements(xml, "longPathAware")
+        .first()
+        .is_some_and(|(_, text)| text.eq_ignore_ascii_case("true"));
+
+    // The first assemblyIdentity describes the image itself, the ones in dependentAssembly its dependencies.
+    let dependencies = xml
+        .split("dependentAssembly")
+        .skip(1)
+        .flat_map(|a| elements(a, "assemblyIdentity").into_iter().take(1))
+        .filter_map(|(tag, _)| Some((attribute(tag, "name")?, attribute(tag, "version")?)))
+        .map(|(name, version)| (name.to_string(), version.to_string()))
+        .collect::<Vec<_>>();
+
+    Manifest {
+        supported_os,
+        max_version_tested,
+        execution_level,
+        dpi_awareness,
+        long_path_aware,
+        dependencies,
+    }
+}
+
+pub fn versions_from_manifest(pe: &PtrPE, info: &mut Info, args: &Args) {
+    let Some(data) = resources_of_type(pe, ResourceID::Manifest).first().copied() else {
+        return;
+    };
+    let xml = String::from_utf8_lossy(data);
+    let manifest = parse_manifest(&xml);
+
+    print_self_reported(
+        format!("Embedded manifest of {} bytes", data.len()),
+        args.quiet,
+    );
+
+    // Reasons the image cannot have been built before a date.
+    let mut built_after = Vec::new();
+
+    if let (Some(lowest), Some(highest)) =
+        (manifest.supported_os.first(), manifest.supported_os.last())
+    {
+        print_self_reported(
+            format!("Manifest declares support for {lowest} to {highest}"),
+            args.quiet,
+        );
+        built_after.push((
+            format!("supportedOS for {highest}"),
+            supported_os_introduced(*highest),
+        ));
+
+        if let Some(os) = info.os() {
+            if os > *highest {
+                print_heuristic(
+                    format!("Operating system version suggests {os}, which is newer than any supported operating system in the manifest"),
+                    args.quiet,
+                );
+            } else if os < *lowest {
+                // supportedOS entries opt into compatibility behaviour, older versions still run the image.
+                print_self_reported(
+                    format!("Operating system version suggests {os}, which runs the image without the compatibility behaviour of any supportedOS entry"),
+                    args.quiet,
+                );
+            }
+        }
+    }
+
+    if let Some(version) = &manifest.max_version_tested {
+        print_self_reported(
+            format!("Manifest has maxversiontested {version}"),
+            args.quiet,
+        );
+        // Introduced with Windows 10 1903.
+        built_after.push((
+            "maxversiontested".to_string(),
+            date_to_datetime(2019, 5, 21),
+        ));
+    }
+
+    if let Some(level) = &manifest.execution_level {
+        print_self_reported(
+            format!("Manifest requests execution level {level}"),
+            args.quiet,
+        );
+    }
+
+    if let Some(dpi_awareness) = &manifest.dpi_awareness {
+        print_self_reported(
+            format!("Manifest declares DPI awareness '{dpi_awareness}'"),
+            args.quiet,
+        );
+    }
+
+    if manifest.long_path_aware {
+        print_self_reported("Manifest declares long path awareness", args.quiet);
+        // Introduced with Windows 10 1607.
+        built_after.push(("longPathAware".to_string(), date_to_datetime(2016, 8, 2)));
+    }
+
+    if let Some((reason, date)) = built_after.into_iter().max_by_key(|(_, date)| *date) {
+        if !info.possible_at_timestamp(date) {
+            info.set_likely_incorrect(LikelyIncorrect::ReleasedAfterTimestamp);
+            print_heuristic(
+                format!(
+                    "Manifest has {reason}, which did not exist before {}, but the timestamp is older",
+                    date.date_naive()
+                ),
+                args.quiet,
+            );
+        }
+    }
+
+    info.set_manifest(manifest);
+}
@@ -0,0 +1,268 @@
+use crate::info::Info;
+use crate::optional_header::OptionalHeader;
+use crate::read::{find_bytes, read_u32};
+use crate::toolchain::Toolchain;
+use crate::{print_heuristic, Args};
+use exe::{
+    Buffer, CCharString, ImageDirectoryEntry, ImportDirectory, PtrPE, SectionCharacteristics, PE,
+};
+
+const MICROSOFT_DOS_STUB_MESSAGE: &[u8] = b"This program cannot be run in DOS mode";
+const RICH_HEADER_MAGIC: &[u8] = b"Rich";
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Signature {
+    LinkerVersion(u8, u8),
+    Section(&'static str),
+    Import(&'static str),
+    /// A string in the initialized data sections, whatever they are named.
+    Banner(&'static str),
+    /// The import directory is in this section, which the Microsoft linker never does for `.data`.
+    ImportDirectoryIn(&'static str),
+    /// The DOS stub does not contain the message emitted by the Microsoft linker and its clones.
+    ForeignDosStub,
+    NoRichHeader,
+}
+
+struct Toolchains {
+    toolchain: Toolchain,
+    /// Any one of these is enough to identify the toolchain.
+    decisive: &'static [Signature],
+    /// All of these are required to identify the toolchain.
+    supporting: &'static [Signature],
+}
+
+const TOOLCHAINS: &[Toolchains] = &[
+    Toolchains {
+        toolchain: Toolchain::Watcom,
+        decisive: &[
+            Signature::Section("AUTO"),
+            Signature::Section("DGROUP"),
+            Signature::Banner("WATCOM C/C++32 Run-Time system"),
+            Signature::Banner("Open Watcom"),
+        ],
+        supporting: &[
+            Signature::LinkerVersion(2, 18),
+            Signature::NoRichHeader,
+            Signature::ForeignDosStub,
+        ],
+    },
+    Toolchains {
+        toolchain: Toolchain::DigitalMars,
+        decisive: &[
+            Signature::Banner("Digital Mars"),
+            Signature::Banner("snn.lib"),
+        ],
+        supporting: &[],
+    },
+    Toolchains {
+        toolchain: Toolchain::LccWin32,
+        decisive: &[
+            Signature::Banner("lcc-win32"),
+            Signature::Banner("lcc-win64"),
+        ],
+        supporting: &[
+            Signature::Import("crtdll.dll"),
+            Signature::NoRichHeader,
+            Signature::ForeignDosStub,
+        ],
+    },
+    Toolchains {
+        toolchain: Toolchain::PellesC,
+        decisive: &[
+            Signature::Import("pocrt.dll"),
+            Signature::Banner("Pelles C"),
+        ],
+        supporting: &[],
+    },
+    Toolchains {
+        toolchain: Toolchain::TinyC,
+        decisive: &[],
+        supporting: &[
+            Signature::LinkerVersion(6, 0),
+            Signature::NoRichHeader,
+            Signature::Import("msvcrt.dll"),
+            Signature::ImportDirectoryIn(".data"),
+        ],
+    },
+    Toolchains {
+        toolchain: Toolchain::FreeBasic,
+        decisive: &[Signature::Banner("FreeBASIC")],
+        supporting: &[],
+    },
+];
+
+struct Image {
+    linker_version: (u8, u8),
+    sections: Vec<String>,
+    imports: Vec<String>,
+    import_section: Option<String>,
+    dos_stub: Option<Vec<u8>>,
+    /// Contents of the initialized data sections, concatenated.
+    data: Vec<u8>,
+}
+
+impl Image {
+    fn new(pe: &PtrPE, optional_header: &OptionalHeader) -> Self {
+        let sections = pe
+            .get_section_table()
+            .map(|sections| {
+                sections
+                    .iter()
+                    .filter_map(|a| a.name.as_str().ok())
+                    .map(|a| a.trim_end_matches('\0').to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let imports = ImportDirectory::parse(pe)
+            .map(|directory| {
+                directory
+                    .descriptors
+                    .iter()
+                    .filter_map(|a| a.get_name(pe).ok())
+                    .filter_map(|a| a.as_str().ok().map(|a| a.to_lowercase()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let import_directory = pe
+            .get_data_directory(ImageDirectoryEntry::Import)
+            .map(|a| a.virtual_address)
+            .ok();
+        let mut import_section = None;
+        let mut data = Vec::new();
+        if let Ok(table) = pe.get_section_table() {
+            for section in table {
+                let Ok(name) = section.name.as_str() else {
+                    continue;
+                };
+                let name = name.trim_end_matches('\0');
+
+                if import_directory.is_some_and(|a| a.0 != 0 && section.has_rva(a)) {
+                    import_section = Some(name.to_string());
+                }
+                // Watcom puts its strings in sections such as DGROUP and CONST instead of .rdata.
+                if section
+                    .characteristics
+                    .contains(SectionCharacteristics::CNT_INITIALIZED_DATA)
+                {
+                    data.extend_from_slice(section.read(pe).unwrap_or_default());
+                }
+            }
+        }
+
+        let file = pe.as_slice();
+        let dos_stub = read_u32(file, 0x3C)
+            .and_then(|e_lfanew| file.get(0x40..e_lfanew as usize))
+            .map(|a| a.to_vec());
+
+        Self {
+            linker_version: (
+                optional_header.major_linker_version,
+                optional_header.minor_linker_version,
+            ),
+            sections,
+            imports,
+            import_section,
+            dos_stub,
+            data,
+        }
+    }
+
+    fn matches(&self, signature: Signature) -> bool {
+        match signature {
+            Signature::LinkerVersion(major, minor) => self.linker_version == (major, minor),
+            Signature::Section(name) => self.sections.iter().any(|a| a == name),
+            Signature::Import(name) => self.imports.iter().any(|a| a == name),
+            Signature::Banner(banner) => find_bytes(&self.data, banner.as_bytes()).is_some(),
+            Signature::ImportDirectoryIn(name) => self.import_section.as_deref() == Some(name),
+            Signature::ForeignDosStub => self
+                .dos_stub
+                .as_deref()
+                .is_some_and(|a| find_bytes(a, MICROSOFT_DOS_STUB_MESSAGE).is_none()),
+            Signature::NoRichHeader => self
+                .dos_stub
+                .as_deref()
+                .is_some_and(|a| find_bytes(a, RICH_HEADER_MAGIC).is_none()),
+        }
+    }
+}
+
+fn describe(signature: Signature) -> String {
+    match signature {
+        Signature::LinkerVersion(major, minor) => format!("Linker version {major}.{minor}"),
+        Signature::Section(name) => format!("Section '{name}'"),
+        Signature::Import(name) => format!("Import of '{name}'"),
+        Signature::Banner(banner) => format!("String '{banner}'"),
+        Signature::ImportDirectoryIn(name) => format!("Import directory in '{name}'"),
+        Signature::ForeignDosStub => "DOS stub not made by a Microsoft linker".to_string(),
+        Signature::NoRichHeader => "Missing Rich header".to_string(),
+    }
+}
+
+/// Whether the toolchain is one of the non-Microsoft native toolchains with a signature in this module.
+pub fn is_minor_toolchain(toolchain: &Toolchain) -> bool {
+    TOOLCHAINS.iter().any(|a| &a.toolchain == toolchain)
+}
+
+/// The detected minor toolchain that is known to write this linker version.
+pub fn toolchain_for_linker_version(info: &Info, major: u8, minor: u8) -> Option<Toolchain> {
+    let detected = info.toolchains();
+
+    TOOLCHAINS
+        .iter()
+        .filter(|a| detected.contains(&a.toolchain))
+        .find(|a| {
+            a.decisive
+                .iter()
+                .chain(a.supporting)
+                .any(|a| *a == Signature::LinkerVersion(major, minor))
+        })
+        .map(|a| a.toolchain.clone())
+}
+
+pub fn versions_from_toolchain_signatures(
+    pe: &PtrPE,
+    optional_header: &OptionalHeader,
+    info: &mut Info,
+    args: &Args,
+) {
+    let image = Image::new(pe, optional_header);
+
+    for toolchain in TOOLCHAINS {
+        let name = &toolchain.toolchain;
+
+        let decisive = toolchain
+            .decisive
+            .iter()
+            .copied()
+            .filter(|a| image.matches(*a))
+            .collect::<Vec<_>>();
+        for signature in &decisive {
+            print_heuristic(
+                format!("{} suggests compiler is {name}", describe(*signature)),
+                args.quiet,
+            );
+        }
+
+        let supporting = !toolchain.supporting.is_empty()
+            && toolchain.supporting.iter().all(|a| image.matches(*a));
+        if supporting {
+            let signatures = toolchain
+                .supporting
+                .iter()
+                .map(|a| describe(*a))
+                .collect::<Vec<_>>()
+                .join(", ");
+            print_heuristic(
+                format!("{signatures} together suggest compiler is {name}"),
+                args.quiet,
+            );
+        }
+
+        if !decisive.is_empty() || supporting {
+            info.add_toolchain(name.clone());
+        }
+    }
+}
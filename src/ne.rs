@@ -0,0 +1,252 @@
+use crate::compiler_version::CompilerVersion;
+use crate::info::Info;
+use crate::likely_incorrect::LikelyIncorrect;
+use crate::read::{read_u16, read_u32, read_u8};
+use crate::versions_for_operating_system::OperatingSystem;
+use crate::{print_heuristic, print_self_reported, Args};
+
+const NE_SIGNATURE: &[u8] = b"NE";
+
+const TARGET_OS_UNKNOWN: u8 = 0;
+const TARGET_OS_OS2: u8 = 1;
+const TARGET_OS_WINDOWS: u8 = 2;
+const TARGET_OS_EUROPEAN_DOS: u8 = 3;
+const TARGET_OS_WINDOWS_386: u8 = 4;
+
+const APPLICATION_FLAGS_LIBRARY: u16 = 0x8000;
+const SEGMENT_FLAGS_DATA: u16 = 0x0001;
+const SEGMENT_ENTRY_SIZE: usize = 8;
+
+/// Modules that first shipped with Windows 3.1, although some were redistributable for Windows 3.0.
+const WINDOWS_3_1_MODULES: &[&str] = &[
+    "COMMDLG", "DDEML", "LZEXPAND", "OLECLI", "OLESVR", "SHELL", "TOOLHELP", "VER",
+];
+
+/// Offset of the NE header if the file is a 16-bit segmented executable.
+pub fn ne_header_offset(data: &[u8]) -> Option<usize> {
+    if data.get(..2) != Some(b"MZ") {
+        return None;
+    }
+
+    let offset = read_u32(data, 0x3C)? as usize;
+    if data.get(offset..offset.checked_add(2)?)? != NE_SIGNATURE {
+        return None;
+    }
+
+    Some(offset)
+}
+
+/// The segmented linker (LINK 5.x) shipped together with the compiler.
+fn compiler_from_linker_version(major: u8, minor: u8) -> Option<CompilerVersion> {
+    Some(match (major, minor) {
+        (5, 0..=19) => CompilerVersion::MicrosoftC6_0,
+        (5, 20..=49) => CompilerVersion::MicrosoftC7_0,
+        (5, 50..=59) => CompilerVersion::VisualCPP1_0,
+        (5, 60..) => CompilerVersion::VisualCPP1_5,
+        _ => {
+            return None;
+        }
+    })
+}
+
+fn expected_windows_version_to_os(major: u8, minor: u8) -> Option<OperatingSystem> {
+    Some(match (major, minor) {
+        (3, 0) => OperatingSystem::Windows3_0,
+        (3, 10) => OperatingSystem::Windows3_1,
+        (3, 95) | (4, 0) => OperatingSystem::Windows95,
+        _ => {
+            return None;
+        }
+    })
+}
+
+fn target_os_name(target_os: u8) -> &'static str {
+    match target_os {
+        TARGET_OS_UNKNOWN => "unknown",
+        TARGET_OS_OS2 => "OS/2",
+        TARGET_OS_WINDOWS => "Windows",
+        TARGET_OS_EUROPEAN_DOS => "European MS-DOS 4.x",
+        TARGET_OS_WINDOWS_386 => "Windows/386",
+        _ => "invalid",
+    }
+}
+
+/// Reads a string prefixed with a single length byte as used by the NE name tables.
+fn read_pascal_string(data: &[u8], offset: usize) -> Option<String> {
+    let length = read_u8(data, offset)? as usize;
+    if length == 0 {
+        return None;
+    }
+
+    let s = data.get(offset + 1..offset + 1 + length)?;
+    Some(String::from_utf8_lossy(s).into_owned())
+}
+
+fn imported_modules(data: &[u8], ne: usize) -> Option<Vec<String>> {
+    let amount = read_u16(data, ne + 0x1E)? as usize;
+    let module_references = ne + read_u16(data, ne + 0x28)? as usize;
+    let imported_names = ne + read_u16(data, ne + 0x2A)? as usize;
+
+    let mut modules = Vec::with_capacity(amount);
+    for i in 0..amount {
+        let offset = read_u16(data, module_references + i * 2)? as usize;
+        modules.push(read_pascal_string(data, imported_names + offset)?);
+    }
+
+    Some(modules)
+}
+
+fn segments(data: &[u8], ne: usize, info: &mut Info, args: &Args) {
+    let (Some(amount), Some(table), Some(alignment)) = (
+        read_u16(data, ne + 0x1C),
+        read_u16(data, ne + 0x22),
+        read_u16(data, ne + 0x32),
+    ) else {
+        return;
+    };
+    let table = ne + table as usize;
+
+    let mut code = 0;
+    let mut data_segments = 0;
+    for i in 0..amount as usize {
+        let entry = table + i * SEGMENT_ENTRY_SIZE;
+        let (Some(sector), Some(flags)) = (read_u16(data, entry), read_u16(data, entry + 4)) else {
+            print_self_reported(
+                format!("Segment table with {amount} segments is outside of the file"),
+                args.quiet,
+            );
+            info.set_likely_incorrect(LikelyIncorrect::Malformed);
+            return;
+        };
+
+        let start = (sector as u64) << alignment.min(31);
+        if start > data.len() as u64 {
+            print_self_reported(
+                format!("Segment {} starts outside of the file", i + 1),
+                args.quiet,
+            );
+            info.set_likely_incorrect(LikelyIncorrect::Malformed);
+        }
+
+        if flags & SEGMENT_FLAGS_DATA != 0 {
+            data_segments += 1;
+        } else {
+            code += 1;
+        }
+    }
+
+    print_self_reported(
+        format!("Segment table has {code} code segments and {data_segments} data segments with a {} byte alignment", 1_u64 << alignment.min(31)),
+        args.quiet,
+    );
+}
+
+pub fn versions_from_ne_header(data: &[u8], ne: usize, info: &mut Info, args: &Args) {
+    let (Some(major), Some(minor), Some(flags), Some(target_os)) = (
+        read_u8(data, ne + 0x02),
+        read_u8(data, ne + 0x03),
+        read_u16(data, ne + 0x0C),
+        read_u8(data, ne + 0x36),
+    ) else {
+        print_self_reported("NE header is truncated", args.quiet);
+        info.set_likely_incorrect(LikelyIncorrect::Malformed);
+        return;
+    };
+
+    let kind = if flags & APPLICATION_FLAGS_LIBRARY != 0 {
+        "library"
+    } else {
+        "application"
+    };
+    print_self_reported(
+        format!(
+            "NE header means this is a 16-bit {kind} for {}",
+            target_os_name(target_os)
+        ),
+        args.quiet,
+    );
+    print_self_reported(
+        "NE executables do not have a timestamp, so versions can not be checked against the compile date",
+        args.quiet,
+    );
+
+    if let Some(module_name) =
+        read_u16(data, ne + 0x26).and_then(|offset| read_pascal_string(data, ne + offset as usize))
+    {
+        print_self_reported(format!("Module name is '{module_name}'"), args.quiet);
+    }
+    if let Some(description) =
+        read_u32(data, ne + 0x2C).and_then(|offset| read_pascal_string(data, offset as usize))
+    {
+        print_self_reported(format!("Module description is '{description}'"), args.quiet);
+    }
+
+    match compiler_from_linker_version(major, minor) {
+        None => print_self_reported(
+            format!("Linker version is unknown, possibly invalid: {major}.{minor}"),
+            args.quiet,
+        ),
+        Some(cv) => {
+            print_self_reported(
+                format!("Linker version is {major}.{minor}, which suggests linker is the one shipped with {cv}"),
+                args.quiet,
+            );
+            info.set_linker_version(cv);
+
+            // The C runtime is statically linked, so the linker is the only hint for the compiler.
+            print_heuristic(
+                format!("Linker shipped with {cv} suggests compiler is {cv}"),
+                args.quiet,
+            );
+            info.insert_single_compiler_version(cv);
+        }
+    }
+
+    segments(data, ne, info, args);
+
+    match imported_modules(data, ne) {
+        None => {
+            print_self_reported("Unable to parse module reference table", args.quiet);
+            info.set_likely_incorrect(LikelyIncorrect::Malformed);
+        }
+        Some(modules) => {
+            for module in &modules {
+                print_self_reported(format!("Imports module '{module}'"), args.quiet);
+
+                if WINDOWS_3_1_MODULES.contains(&module.to_uppercase().as_str()) {
+                    print_heuristic(
+                        format!("Import of module '{module}' suggests at least Windows 3.1"),
+                        args.quiet,
+                    );
+                }
+            }
+        }
+    }
+
+    if !matches!(target_os, TARGET_OS_WINDOWS | TARGET_OS_WINDOWS_386) {
+        return;
+    }
+
+    let (Some(windows_minor), Some(windows_major)) =
+        (read_u8(data, ne + 0x3E), read_u8(data, ne + 0x3F))
+    else {
+        return;
+    };
+
+    match expected_windows_version_to_os(windows_major, windows_minor) {
+        None => print_self_reported(
+            format!("Expected Windows version is unknown: {windows_major}.{windows_minor}"),
+            args.quiet,
+        ),
+        Some(os) => {
+            print_self_reported(
+                format!("Expected Windows version is {windows_major}.{windows_minor} which suggests {os}"),
+                args.quiet,
+            );
+
+            // NE executables only have a single version, which is used for both the operating system and subsystem.
+            info.set_os(os);
+            info.set_subsystem(os);
+        }
+    }
+}
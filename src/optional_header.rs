@@ -8,6 +8,8 @@ pub struct OptionalHeader {
     pub minor_operating_system_version: u16,
     pub major_subsystem_version: u16,
     pub minor_subsystem_version: u16,
+    pub subsystem: u16,
+    pub check_sum: u32,
 }
 
 impl OptionalHeader {
@@ -19,6 +21,8 @@ impl OptionalHeader {
             minor_operating_system_version: header.minor_operating_system_version,
             major_subsystem_version: header.major_subsystem_version,
             minor_subsystem_version: header.minor_subsystem_version,
+            subsystem: header.subsystem,
+            check_sum: header.check_sum,
         }
     }
 
@@ -30,6 +34,8 @@ impl OptionalHeader {
             minor_operating_system_version: header.minor_operating_system_version,
             major_subsystem_version: header.major_subsystem_version,
             minor_subsystem_version: header.minor_subsystem_version,
+            subsystem: header.subsystem,
+            check_sum: header.check_sum,
         }
     }
 }
@@ -0,0 +1,186 @@
+//! Names of functions that are usually imported by ordinal.
+
+use crate::compiler_version::CompilerVersion;
+
+/// OLE Automation, the ordinals have been the same since the 16-bit OLE2DISP.
+const OLEAUT32: &[(u16, &str)] = &[
+    (2, "SysAllocString"),
+    (3, "SysReAllocString"),
+    (4, "SysAllocStringLen"),
+    (5, "SysReAllocStringLen"),
+    (6, "SysFreeString"),
+    (7, "SysStringLen"),
+    (8, "VariantInit"),
+    (9, "VariantClear"),
+    (10, "VariantCopy"),
+    (11, "VariantCopyInd"),
+    (12, "VariantChangeType"),
+    (13, "VariantTimeToDosDateTime"),
+    (14, "DosDateTimeToVariantTime"),
+    (15, "SafeArrayCreate"),
+    (16, "SafeArrayDestroy"),
+    (17, "SafeArrayGetDim"),
+    (18, "SafeArrayGetElemsize"),
+    (19, "SafeArrayGetUBound"),
+    (20, "SafeArrayGetLBound"),
+    (21, "SafeArrayLock"),
+    (22, "SafeArrayUnlock"),
+    (23, "SafeArrayAccessData"),
+    (24, "SafeArrayUnaccessData"),
+    (25, "SafeArrayGetElement"),
+    (26, "SafeArrayPutElement"),
+    (27, "SafeArrayCopy"),
+    (28, "DispGetParam"),
+    (29, "DispGetIDsOfNames"),
+    (30, "DispInvoke"),
+    (31, "CreateDispTypeInfo"),
+    (32, "CreateStdDispatch"),
+    (33, "RegisterActiveObject"),
+    (34, "RevokeActiveObject"),
+    (35, "GetActiveObject"),
+    (36, "SafeArrayAllocDescriptor"),
+    (37, "SafeArrayAllocData"),
+    (38, "SafeArrayDestroyDescriptor"),
+    (39, "SafeArrayDestroyData"),
+    (40, "SafeArrayRedim"),
+    (147, "VariantChangeTypeEx"),
+    (148, "SafeArrayPtrOfIndex"),
+    (149, "SysStringByteLen"),
+    (150, "SysAllocStringByteLen"),
+    (161, "LoadTypeLib"),
+    (162, "LoadRegTypeLib"),
+    (163, "RegisterTypeLib"),
+    (164, "QueryPathOfRegTypeLib"),
+    (183, "LoadTypeLibEx"),
+    (184, "SystemTimeToVariantTime"),
+    (185, "VariantTimeToSystemTime"),
+    (186, "UnRegisterTypeLib"),
+    (200, "GetErrorInfo"),
+    (201, "SetErrorInfo"),
+    (202, "CreateErrorInfo"),
+    (415, "OleIconToCursor"),
+    (416, "OleCreatePropertyFrameIndirect"),
+    (417, "OleCreatePropertyFrame"),
+    (418, "OleLoadPicture"),
+    (419, "OleCreatePictureIndirect"),
+    (420, "OleCreateFontIndirect"),
+    (421, "OleTranslateColor"),
+];
+
+/// The ANSI build of MFC 4.2, which Visual C++ 4.2, 5.0 and 6.0 all link against.
+///
+/// Members are decorated, so they are matched like C++ symbols imported by name.
+const MFC42: &[(u16, &str)] = &[
+    (535, "??0CString@@QAE@ABV0@@Z"),
+    (537, "??0CString@@QAE@PBD@Z"),
+    (540, "??0CString@@QAE@XZ"),
+    (800, "??1CString@@QAE@XZ"),
+    (823, "??2@YAPAXI@Z"),
+    (825, "??3@YAXPAX@Z"),
+    (858, "??4CString@@QAEABV0@ABV0@@Z"),
+    (860, "??4CString@@QAEABV0@PBD@Z"),
+    (1576, "?AfxWinMain@@YGHPAUHINSTANCE__@@0PADH@Z"),
+    (2514, "?DoModal@CDialog@@UAEHXZ"),
+];
+
+/// The ATL DLL that Windows ships, ATL 3.0 added the control hosting functions from ordinal 34.
+const ATL: &[(u16, &str)] = &[
+    (10, "AtlAdvise"),
+    (11, "AtlUnadvise"),
+    (12, "AtlFreeMarshalStream"),
+    (13, "AtlMarshalPtrInProc"),
+    (14, "AtlUnmarshalPtr"),
+    (15, "AtlModuleGetClassObject"),
+    (16, "AtlModuleInit"),
+    (17, "AtlModuleRegisterClassObjects"),
+    (18, "AtlModuleRegisterServer"),
+    (19, "AtlModuleRegisterTypeLib"),
+    (20, "AtlModuleRevokeClassObjects"),
+    (21, "AtlModuleTerm"),
+    (22, "AtlModuleUnregisterServer"),
+    (23, "AtlModuleUpdateRegistryFromResourceD"),
+    (24, "AtlWaitWithMessageLoop"),
+    (25, "AtlSetErrorInfo"),
+    (26, "AtlCreateTargetDC"),
+    (27, "AtlHiMetricToPixel"),
+    (28, "AtlPixelToHiMetric"),
+    (29, "AtlDevModeW2A"),
+    (30, "AtlComPtrAssign"),
+    (31, "AtlComQIPtrAssign"),
+    (32, "AtlInternalQueryInterface"),
+    (34, "AtlGetVersion"),
+    (35, "AtlAxDialogBoxW"),
+    (36, "AtlAxDialogBoxA"),
+    (37, "AtlAxCreateDialogW"),
+    (38, "AtlAxCreateDialogA"),
+    (39, "AtlAxCreateControl"),
+    (40, "AtlAxCreateControlEx"),
+    (41, "AtlAxAttachControl"),
+    (42, "AtlAxWinInit"),
+    (43, "AtlModuleAddCreateWndData"),
+    (44, "AtlModuleExtractCreateWndData"),
+    (45, "AtlModuleRegisterWndClassInfoW"),
+    (46, "AtlModuleRegisterWndClassInfoA"),
+    (47, "AtlAxGetControl"),
+    (48, "AtlAxGetHost"),
+    (49, "AtlRegisterClassCategoriesHelper"),
+    (50, "AtlIPersistStreamInit_Load"),
+    (51, "AtlIPersistStreamInit_Save"),
+    (52, "AtlIPersistPropertyBag_Load"),
+    (53, "AtlIPersistPropertyBag_Save"),
+    (54, "AtlGetObjectSourceInterface"),
+    (55, "AtlModuleUnRegisterTypeLib"),
+    (56, "AtlModuleLoadTypeLib"),
+    (57, "AtlModuleUnregisterServerEx"),
+    (58, "AtlModuleAddTermFunc"),
+];
+
+struct Table {
+    module: &'static str,
+    names: &'static [(u16, &'static str)],
+    /// Ordinals from the first of each pair were only exported from the release that shipped with the version.
+    introduced: &'static [(u16, CompilerVersion)],
+}
+
+const TABLES: &[Table] = &[
+    Table {
+        module: "oleaut32.dll",
+        names: OLEAUT32,
+        introduced: &[],
+    },
+    Table {
+        module: "mfc42.dll",
+        names: MFC42,
+        introduced: &[],
+    },
+    Table {
+        module: "atl.dll",
+        names: ATL,
+        introduced: &[(34, CompilerVersion::VisualCPP6_0)],
+    },
+];
+
+fn table(module: &str) -> Option<&'static Table> {
+    TABLES
+        .iter()
+        .find(|a| a.module.eq_ignore_ascii_case(module))
+}
+
+/// The name of a function imported by ordinal from `module`, if the DLL has fixed ordinals.
+pub fn ordinal_name(module: &str, ordinal: u16) -> Option<&'static str> {
+    table(module)?
+        .names
+        .iter()
+        .find(|(a, _)| *a == ordinal)
+        .map(|(_, name)| *name)
+}
+
+/// First version whose DLL exports the ordinal, for ordinals that were added to an existing DLL.
+pub fn ordinal_introduced(module: &str, ordinal: u16) -> Option<CompilerVersion> {
+    table(module)?
+        .introduced
+        .iter()
+        .filter(|(first, _)| ordinal >= *first)
+        .map(|(_, version)| *version)
+        .max()
+}
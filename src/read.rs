@@ -0,0 +1,58 @@
+use exe::{Buffer, PtrPE, PE, RVA};
+
+pub fn read_u8(data: &[u8], offset: usize) -> Option<u8> {
+    data.get(offset).copied()
+}
+
+pub fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset.checked_add(2)?)?;
+    Some(u16::from_le_bytes(bytes.try_into().ok()?))
+}
+
+pub fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+pub fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    let bytes = data.get(offset..offset.checked_add(8)?)?;
+    Some(u64::from_le_bytes(bytes.try_into().ok()?))
+}
+
+/// Reads a null terminated string, replacing invalid UTF-8.
+pub fn read_cstring(data: &[u8], offset: usize) -> Option<String> {
+    let bytes = data.get(offset..)?;
+    let end = bytes.iter().position(|a| *a == 0)?;
+
+    Some(String::from_utf8_lossy(&bytes[..end]).into_owned())
+}
+
+/// Reads `size` bytes starting at the relative virtual address `rva`.
+pub fn read_rva(pe: &PtrPE, rva: u32, size: u32) -> Option<&[u8]> {
+    if rva == 0 {
+        return None;
+    }
+
+    let offset = pe.rva_to_offset(RVA(rva)).ok()?.0 as usize;
+    pe.as_slice()
+        .get(offset..offset.checked_add(size as usize)?)
+}
+
+/// Reads a null terminated string at the relative virtual address `rva`.
+pub fn read_rva_cstring(pe: &PtrPE, rva: u32) -> Option<String> {
+    if rva == 0 {
+        return None;
+    }
+
+    let offset = pe.rva_to_offset(RVA(rva)).ok()?.0 as usize;
+    read_cstring(pe.as_slice(), offset)
+}
+
+/// Finds the first occurrence of `needle` in `data`.
+pub fn find_bytes(data: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() {
+        return None;
+    }
+
+    data.windows(needle.len()).position(|a| a == needle)
+}
@@ -0,0 +1,16 @@
+use crate::read::read_rva;
+use exe::{PtrPE, ResolvedDirectoryID, ResourceDirectory, ResourceID};
+
+/// Data of every resource with the given type, in the order of the resource directory.
+pub fn resources_of_type(pe: &PtrPE, kind: ResourceID) -> Vec<&[u8]> {
+    let Ok(directory) = ResourceDirectory::parse(pe) else {
+        return Vec::new();
+    };
+
+    directory
+        .filter(Some(ResolvedDirectoryID::ID(kind as u32)), None, None)
+        .iter()
+        .filter_map(|a| a.get_data_entry(pe).ok())
+        .filter_map(|a| read_rva(pe, a.offset_to_data.0, a.size))
+        .collect()
+}
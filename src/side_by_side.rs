@@ -0,0 +1,166 @@
+use crate::compiler_version::{date_to_datetime, CompilerVersion};
+use crate::info::Info;
+use crate::likely_incorrect::LikelyIncorrect;
+use crate::{print_heuristic, print_self_reported, Args};
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct CrtBuild {
+    pub version: &'static str,
+    pub compiler: CompilerVersion,
+    pub release: &'static str,
+}
+
+/// Versions written into the manifest by the CRT, MFC and ATL headers of each release.
+///
+/// The RTM header of Visual Studio 2005 refers to 8.0.50608.0 even though the DLLs are 8.0.50727.42.
+const CRT_BUILDS: &[(CrtBuild, (i32, u32, u32))] = &[
+    (
+        CrtBuild {
+            version: "8.0.50608.0",
+            compiler: CompilerVersion::VisualStudio2005,
+            release: "RTM",
+        },
+        (2005, 11, 7),
+    ),
+    (
+        CrtBuild {
+            version: "8.0.50727.42",
+            compiler: CompilerVersion::VisualStudio2005,
+            release: "RTM",
+        },
+        (2005, 11, 7),
+    ),
+    (
+        CrtBuild {
+            version: "8.0.50727.762",
+            compiler: CompilerVersion::VisualStudio2005,
+            release: "SP1",
+        },
+        (2006, 12, 14),
+    ),
+    (
+        CrtBuild {
+            version: "8.0.50727.4053",
+            compiler: CompilerVersion::VisualStudio2005,
+            release: "SP1 with the ATL security update (KB971090)",
+        },
+        (2009, 7, 28),
+    ),
+    (
+        CrtBuild {
+            version: "8.0.50727.6195",
+            compiler: CompilerVersion::VisualStudio2005,
+            release: "SP1 with the MFC security update (KB2538218)",
+        },
+        (2011, 6, 14),
+    ),
+    (
+        CrtBuild {
+            version: "9.0.21022.8",
+            compiler: CompilerVersion::VisualStudio2008,
+            release: "RTM",
+        },
+        (2007, 11, 19),
+    ),
+    (
+        CrtBuild {
+            version: "9.0.30411.0",
+            compiler: CompilerVersion::VisualStudio2008,
+            release: "Feature Pack",
+        },
+        (2008, 4, 7),
+    ),
+    (
+        CrtBuild {
+            version: "9.0.30729.1",
+            compiler: CompilerVersion::VisualStudio2008,
+            release: "SP1",
+        },
+        (2008, 8, 11),
+    ),
+    (
+        CrtBuild {
+            version: "9.0.30729.4148",
+            compiler: CompilerVersion::VisualStudio2008,
+            release: "SP1 with the ATL security update (KB971092)",
+        },
+        (2009, 7, 28),
+    ),
+    (
+        CrtBuild {
+            version: "9.0.30729.6161",
+            compiler: CompilerVersion::VisualStudio2008,
+            release: "SP1 with the MFC security update (KB2538241)",
+        },
+        (2011, 6, 14),
+    ),
+];
+
+fn crt_build(version: &str) -> Option<(CrtBuild, DateTime<Utc>)> {
+    CRT_BUILDS
+        .iter()
+        .find(|(a, _)| a.version == version)
+        .map(|(a, (year, month, day))| (*a, date_to_datetime(*year, *month, *day)))
+}
+
+fn is_visual_cpp_assembly(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name.starts_with("microsoft.vc80.") || name.starts_with("microsoft.vc90.")
+}
+
+pub fn versions_from_side_by_side(info: &mut Info, args: &Args) {
+    let Some(manifest) = info.manifest() else {
+        return;
+    };
+
+    let dependencies = manifest
+        .dependencies
+        .iter()
+        .filter(|(name, _)| is_visual_cpp_assembly(name))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    // Every assembly comes from the same headers, so the newest one is the most precise.
+    let mut newest: Option<(CrtBuild, DateTime<Utc>)> = None;
+    for (name, version) in dependencies {
+        let Some((build, date)) = crt_build(&version) else {
+            print_self_reported(
+                format!("Manifest depends on {name} {version}, which is an unknown build"),
+                args.quiet,
+            );
+            continue;
+        };
+
+        print_self_reported(
+            format!(
+                "Manifest depends on {name} {version}, which suggests {} {}",
+                build.compiler, build.release
+            ),
+            args.quiet,
+        );
+        if newest.is_none_or(|(_, a)| date > a) {
+            newest = Some((build, date));
+        }
+    }
+
+    let Some((build, date)) = newest else {
+        return;
+    };
+
+    if !info.possible_at_timestamp(date) {
+        info.set_likely_incorrect(LikelyIncorrect::ReleasedAfterTimestamp);
+        print_heuristic(
+            format!(
+                "{} {} was released on {}, which is after the timestamp",
+                build.compiler,
+                build.release,
+                date.date_naive()
+            ),
+            args.quiet,
+        );
+    }
+
+    info.exclude_all_compiler_versions_except(build.compiler);
+    info.set_crt_build(build);
+}
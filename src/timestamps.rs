@@ -0,0 +1,227 @@
+use crate::bound_imports::parse_bound_imports;
+use crate::info::Info;
+use crate::likely_incorrect::LikelyIncorrect;
+use crate::read::{read_rva, read_u32};
+use crate::{print_heuristic, print_self_reported, Args};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use exe::{ImageDirectoryEntry, PtrPE, PE};
+
+/// Written by the Borland linker of Delphi 4 to 2006 instead of the link time.
+pub const DELPHI_TIMESTAMP: u32 = 0x2A425E19;
+
+const IMAGE_DEBUG_DIRECTORY_SIZE: usize = 28;
+const IMAGE_DEBUG_TYPE_REPRO: u32 = 16;
+
+fn debug_type_name(kind: u32) -> &'static str {
+    match kind {
+        1 => "COFF",
+        2 => "CodeView",
+        3 => "FPO",
+        4 => "Misc",
+        5 => "Exception",
+        6 => "Fixup",
+        9 => "Borland",
+        11 => "CLSID",
+        12 => "VC feature",
+        13 => "POGO",
+        14 => "ILTCG",
+        16 => "REPRO",
+        17 => "Embedded portable PDB",
+        19 => "PDB checksum",
+        20 => "Extended DLL characteristics",
+        _ => "Unknown",
+    }
+}
+
+/// A timestamp found in the image.
+struct Entry {
+    source: String,
+    raw: u32,
+    date: DateTime<Utc>,
+    /// Whether the timestamp is for this image, which is not the case for bound imports.
+    own: bool,
+}
+
+fn directory(pe: &PtrPE, entry: ImageDirectoryEntry) -> Option<&[u8]> {
+    let directory = pe.get_data_directory(entry).ok()?;
+    read_rva(pe, directory.virtual_address.0, directory.size)
+}
+
+/// Type and `TimeDateStamp` of every debug directory entry.
+fn debug_entries(pe: &PtrPE) -> Vec<(u32, u32)> {
+    let Some(directory) = directory(pe, ImageDirectoryEntry::Debug) else {
+        return Vec::new();
+    };
+
+    directory
+        .chunks_exact(IMAGE_DEBUG_DIRECTORY_SIZE)
+        .filter_map(|a| Some((read_u32(a, 12)?, read_u32(a, 4)?)))
+        .collect()
+}
+
+/// Images linked with /Brepro have a hash of the contents instead of the link time.
+pub fn is_reproducible(pe: &PtrPE) -> bool {
+    debug_entries(pe)
+        .iter()
+        .any(|(kind, _)| *kind == IMAGE_DEBUG_TYPE_REPRO)
+}
+
+/// Borland linkers write the resource directory timestamp as an MS-DOS date and time.
+fn dos_date_time(value: u32) -> Option<DateTime<Utc>> {
+    let date = value >> 16;
+    let time = value & 0xFFFF;
+
+    let date = NaiveDate::from_ymd_opt(1980 + (date >> 9) as i32, (date >> 5) & 0xF, date & 0x1F)?;
+    let time = NaiveTime::from_hms_opt(time >> 11, (time >> 5) & 0x3F, (time & 0x1F) * 2)?;
+
+    Some(DateTime::from_naive_utc_and_offset(
+        NaiveDateTime::new(date, time),
+        Utc,
+    ))
+}
+
+fn unix_time(value: u32) -> DateTime<Utc> {
+    DateTime::from_timestamp(value.into(), 0).unwrap()
+}
+
+fn distance(a: DateTime<Utc>, b: DateTime<Utc>) -> Duration {
+    (a - b).abs()
+}
+
+fn entries(
+    pe: &PtrPE,
+    coff: Option<DateTime<Utc>>,
+    delphi: bool,
+    reproducible: bool,
+) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    let mut push = |source: String, raw: u32, date: DateTime<Utc>, own: bool| {
+        entries.push(Entry {
+            source,
+            raw,
+            date,
+            own,
+        })
+    };
+
+    if let Some(raw) = directory(pe, ImageDirectoryEntry::Export).and_then(|a| read_u32(a, 4)) {
+        push(
+            "Export directory".to_string(),
+            raw,
+            unix_time(raw),
+            !reproducible,
+        );
+    }
+
+    for (kind, raw) in debug_entries(pe) {
+        push(
+            format!("{} debug directory", debug_type_name(kind)),
+            raw,
+            unix_time(raw),
+            !reproducible,
+        );
+    }
+
+    if let Some(raw) = directory(pe, ImageDirectoryEntry::Resource).and_then(|a| read_u32(a, 4)) {
+        // The format is unknown, so use the one that agrees with the COFF timestamp or the Delphi default.
+        let unix = unix_time(raw);
+        let date = match (dos_date_time(raw), coff) {
+            (Some(dos), Some(coff)) if distance(dos, coff) < distance(unix, coff) => dos,
+            (Some(dos), None) if delphi => dos,
+            _ => unix,
+        };
+        push("Resource directory".to_string(), raw, date, true);
+    }
+
+    if let Some(raw) = directory(pe, ImageDirectoryEntry::LoadConfig).and_then(|a| read_u32(a, 4)) {
+        push("Load config".to_string(), raw, unix_time(raw), true);
+    }
+
+    for import in parse_bound_imports(pe) {
+        push(
+            format!("Bound import of {}", import.name),
+            import.timestamp,
+            unix_time(import.timestamp),
+            false,
+        );
+    }
+
+    entries
+}
+
+pub fn versions_from_timestamps(pe: &PtrPE, coff: u32, info: &mut Info, args: &Args) {
+    let reproducible = is_reproducible(pe);
+    // A zero, hashed or Delphi timestamp does not say when the image was linked.
+    let coff_date = info
+        .timestamp()
+        .filter(|_| coff != 0 && coff != DELPHI_TIMESTAMP && !reproducible);
+
+    // Timestamps that repeat a hashed or zero COFF timestamp are not dates either.
+    let entries = entries(pe, coff_date, coff == DELPHI_TIMESTAMP, reproducible)
+        .into_iter()
+        .filter(|a| a.raw != 0 && a.raw != u32::MAX && (a.raw != coff || coff_date.is_some()))
+        .collect::<Vec<_>>();
+    if entries.is_empty() {
+        return;
+    }
+
+    // Bound imports are shown with the rest of the bound import directory.
+    for entry in entries.iter().filter(|a| a.raw != coff && a.own) {
+        print_self_reported(
+            format!("{} timestamp is {}", entry.source, entry.date),
+            args.quiet,
+        );
+    }
+
+    let own = entries
+        .iter()
+        .filter(|a| a.own && a.raw != DELPHI_TIMESTAMP)
+        .collect::<Vec<_>>();
+    match coff_date {
+        Some(coff_date) => {
+            for entry in own {
+                let difference = distance(entry.date, coff_date);
+                if difference > Duration::days(365) {
+                    info.set_likely_incorrect(LikelyIncorrect::TimestampsDisagree);
+                    print_heuristic(
+                        format!(
+                            "{} timestamp is {} days from the COFF timestamp, which means one of them was modified",
+                            entry.source,
+                            difference.num_days()
+                        ),
+                        args.quiet,
+                    );
+                } else if difference > Duration::days(1) {
+                    print_heuristic(
+                        format!(
+                            "{} timestamp is {} days from the COFF timestamp",
+                            entry.source,
+                            difference.num_days()
+                        ),
+                        args.quiet,
+                    );
+                }
+            }
+        }
+        None => {
+            // Entries are in the order they are trusted, the linker writes the export and debug timestamps.
+            if let Some(entry) = own.first() {
+                print_heuristic(
+                    format!(
+                        "{} timestamp {} is used since the COFF timestamp is not the link time",
+                        entry.source, entry.date
+                    ),
+                    args.quiet,
+                );
+                info.set_timestamp(entry.date);
+            }
+        }
+    }
+
+    if let Some(coff_date) = coff_date {
+        info.add_to_timeline(coff_date, "COFF header".to_string());
+    }
+    for entry in entries {
+        info.add_to_timeline(entry.date, entry.source);
+    }
+}
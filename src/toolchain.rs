@@ -0,0 +1,88 @@
+use std::fmt::Formatter;
+
+/// Compilers and runtimes that are not described by a Visual C++ version.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Toolchain {
+    DotNet { runtime: String, form: DotNetForm },
+    VisualBasic5_0,
+    VisualBasic6_0,
+    IntelCppClassic,
+    IntelCppOneApi,
+    IntelFortranClassic,
+    IntelFortranOneApi,
+    Watcom,
+    DigitalMars,
+    LccWin32,
+    PellesC,
+    TinyC,
+    FreeBasic,
+    MicrosoftC,
+    TurboC,
+    BorlandCpp,
+    TurboPascal,
+    Djgpp,
+    Gnu,
+    Llvm,
+    Borland32,
+    EmbeddedVisualCpp3_0,
+    EmbeddedVisualCpp4_0,
+    VisualStudio2005SmartDevice,
+    VisualStudio2008SmartDevice,
+    EdkGenFw,
+    PlatformSdkIa64,
+}
+
+impl std::fmt::Display for Toolchain {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Toolchain::DotNet { runtime, form } => write!(f, "{runtime} ({form})"),
+            Toolchain::VisualBasic5_0 => f.write_str("Visual Basic 5.0"),
+            Toolchain::VisualBasic6_0 => f.write_str("Visual Basic 6.0"),
+            Toolchain::IntelCppClassic => f.write_str("Intel C++ Compiler Classic (ICL)"),
+            Toolchain::IntelCppOneApi => f.write_str("Intel oneAPI DPC++/C++ Compiler (ICX)"),
+            Toolchain::IntelFortranClassic => f.write_str("Intel Fortran Compiler Classic (IFORT)"),
+            Toolchain::IntelFortranOneApi => f.write_str("Intel Fortran Compiler (IFX)"),
+            Toolchain::Watcom => f.write_str("Watcom C/C++ or Open Watcom"),
+            Toolchain::DigitalMars => f.write_str("Digital Mars C/C++"),
+            Toolchain::LccWin32 => f.write_str("LCC-Win32"),
+            Toolchain::PellesC => f.write_str("Pelles C"),
+            Toolchain::TinyC => f.write_str("Tiny C Compiler"),
+            Toolchain::FreeBasic => f.write_str("FreeBASIC"),
+            Toolchain::MicrosoftC => f.write_str("Microsoft C 5.x or QuickC"),
+            Toolchain::TurboC => f.write_str("Turbo C"),
+            Toolchain::BorlandCpp => f.write_str("Borland C++"),
+            Toolchain::TurboPascal => f.write_str("Turbo Pascal"),
+            Toolchain::Djgpp => f.write_str("DJGPP"),
+            Toolchain::Gnu => f.write_str("GCC or another GNU ld based toolchain"),
+            Toolchain::Llvm => f.write_str("Clang or another LLVM lld based toolchain"),
+            Toolchain::Borland32 => f.write_str("Borland C++ Builder or Delphi"),
+            Toolchain::EmbeddedVisualCpp3_0 => f.write_str("eMbedded Visual C++ 3.0"),
+            Toolchain::EmbeddedVisualCpp4_0 => f.write_str("eMbedded Visual C++ 4.0"),
+            Toolchain::VisualStudio2005SmartDevice => {
+                f.write_str("Visual Studio 2005 (Smart Device)")
+            }
+            Toolchain::VisualStudio2008SmartDevice => {
+                f.write_str("Visual Studio 2008 (Smart Device)")
+            }
+            Toolchain::EdkGenFw => f.write_str("GCC or Clang through EDK2 GenFw"),
+            Toolchain::PlatformSdkIa64 => f.write_str("Platform SDK IA-64 compiler"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum DotNetForm {
+    ReadyToRun,
+    NativeAot,
+    SingleFileBundle,
+}
+
+impl std::fmt::Display for DotNetForm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            DotNetForm::ReadyToRun => "ReadyToRun",
+            DotNetForm::NativeAot => "NativeAOT",
+            DotNetForm::SingleFileBundle => "single-file bundle",
+        })
+    }
+}
@@ -0,0 +1,102 @@
+use crate::dos_stub::rich_header_offset;
+use crate::info::Info;
+use crate::optional_header::OptionalHeader;
+use crate::read::read_u32;
+use crate::toolchain::Toolchain;
+use crate::{print_heuristic, print_self_reported, Args};
+use exe::{Buffer, CCharString, PtrPE, PE};
+
+pub const IMAGE_SUBSYSTEM_EFI_APPLICATION: u16 = 10;
+pub const IMAGE_SUBSYSTEM_EFI_BOOT_SERVICE_DRIVER: u16 = 11;
+pub const IMAGE_SUBSYSTEM_EFI_RUNTIME_DRIVER: u16 = 12;
+pub const IMAGE_SUBSYSTEM_EFI_ROM: u16 = 13;
+
+/// Sections written by GenFw when converting an ELF image, it does not keep any others.
+const GENFW_SECTIONS: &[&str] = &[".text", ".data", ".reloc"];
+
+fn efi_subsystem_name(subsystem: u16) -> Option<&'static str> {
+    Some(match subsystem {
+        IMAGE_SUBSYSTEM_EFI_APPLICATION => "EFI application",
+        IMAGE_SUBSYSTEM_EFI_BOOT_SERVICE_DRIVER => "EFI boot service driver",
+        IMAGE_SUBSYSTEM_EFI_RUNTIME_DRIVER => "EFI runtime driver",
+        IMAGE_SUBSYSTEM_EFI_ROM => "EFI ROM",
+        _ => {
+            return None;
+        }
+    })
+}
+
+pub fn is_efi(header: &OptionalHeader) -> bool {
+    efi_subsystem_name(header.subsystem).is_some()
+}
+
+pub fn versions_from_efi(
+    pe: &PtrPE,
+    header: &OptionalHeader,
+    timestamp: u32,
+    info: &mut Info,
+    args: &Args,
+) {
+    let Some(subsystem) = efi_subsystem_name(header.subsystem) else {
+        return;
+    };
+
+    print_self_reported(
+        format!("Subsystem is {subsystem}, which means operating system versions do not apply"),
+        args.quiet,
+    );
+    info.set_efi_subsystem(subsystem);
+
+    let data = pe.as_slice();
+    let e_lfanew = read_u32(data, 0x3C).unwrap_or(0) as usize;
+    let has_rich_header = rich_header_offset(data, e_lfanew).is_some();
+
+    let sections = pe
+        .get_section_table()
+        .map(|sections| {
+            sections
+                .iter()
+                .filter_map(|a| a.name.as_str().ok())
+                .map(|a| a.trim_end_matches('\0').to_string())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    let mut evidence = Vec::new();
+    if timestamp == 0 {
+        evidence.push("zero timestamp");
+    }
+    if e_lfanew <= 0x40 {
+        evidence.push("no DOS stub");
+    }
+    if !has_rich_header {
+        evidence.push("no Rich header");
+    }
+    if (header.major_linker_version, header.minor_linker_version) == (0, 0) {
+        evidence.push("linker version 0.0");
+    }
+    if !sections.is_empty()
+        && sections
+            .iter()
+            .all(|a| GENFW_SECTIONS.contains(&a.as_str()))
+        && sections.last().is_some_and(|a| a == ".reloc")
+    {
+        evidence.push("only .text, .data and a trailing .reloc section");
+    }
+
+    if has_rich_header {
+        print_heuristic(
+            "EFI image has a Rich header, which suggests it was linked by the Microsoft linker",
+            args.quiet,
+        );
+    } else if evidence.len() >= 3 {
+        print_heuristic(
+            format!(
+                "EFI image has {}, which suggests an ELF image converted by EDK2 GenFw",
+                evidence.join(", ")
+            ),
+            args.quiet,
+        );
+        info.add_toolchain(Toolchain::EdkGenFw);
+    }
+}
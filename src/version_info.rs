@@ -0,0 +1,334 @@
+use crate::info::Info;
+use crate::likely_incorrect::LikelyIncorrect;
+use crate::read::{read_u16, read_u32};
+use crate::resources::resources_of_type;
+use crate::{print_heuristic, print_self_reported, Args};
+use chrono::Datelike;
+use exe::{CCharString, ImportDirectory, PtrPE, ResourceID};
+
+const VS_FFI_SIGNATURE: u32 = 0xFEEF04BD;
+const VS_FIXEDFILEINFO_SIZE: usize = 52;
+
+const FILE_FLAGS: &[(u32, &str)] = &[
+    (0x01, "DEBUG"),
+    (0x02, "PRERELEASE"),
+    (0x04, "PATCHED"),
+    (0x08, "PRIVATEBUILD"),
+    (0x10, "INFOINFERRED"),
+    (0x20, "SPECIALBUILD"),
+];
+
+/// Strings from the `StringFileInfo` table that are reported and their names, in the order they are printed.
+const REPORTED_STRINGS: &[(&str, &str)] = &[
+    ("CompanyName", "Company"),
+    ("ProductName", "Product"),
+    ("FileDescription", "Description"),
+    ("OriginalFilename", "Original Filename"),
+    ("LegalCopyright", "Copyright"),
+];
+
+/// Debug versions of the C and C++ runtimes, which are only used by debug builds.
+const DEBUG_RUNTIMES: &[&str] = &[
+    "msvcrtd.dll",
+    "msvcr70d.dll",
+    "msvcr71d.dll",
+    "msvcr80d.dll",
+    "msvcr90d.dll",
+    "msvcr100d.dll",
+    "msvcr110d.dll",
+    "msvcr120d.dll",
+    "msvcp140d.dll",
+    "vcruntime140d.dll",
+    "ucrtbased.dll",
+];
+
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct VersionInfo {
+    pub file_version: String,
+    pub product_version: String,
+    pub flags: Vec<&'static str>,
+    pub file_os: &'static str,
+    pub file_type: &'static str,
+    pub strings: Vec<(&'static str, String)>,
+}
+
+impl VersionInfo {
+    pub fn is_debug(&self) -> bool {
+        self.flags.contains(&"DEBUG")
+    }
+}
+
+fn file_os_name(file_os: u32) -> &'static str {
+    match file_os {
+        0x00000 => "Unknown",
+        0x00001 => "16-bit Windows",
+        0x00004 => "32-bit Windows",
+        0x10001 => "16-bit Windows on MS-DOS",
+        0x10004 => "32-bit Windows on MS-DOS",
+        0x20000 | 0x30000 => "OS/2",
+        0x40000 => "Windows NT",
+        0x40004 => "Windows NT (32-bit Windows)",
+        _ => "Unknown",
+    }
+}
+
+fn file_type_name(file_type: u32) -> &'static str {
+    match file_type {
+        1 => "Application",
+        2 => "DLL",
+        3 => "Driver",
+        4 => "Font",
+        5 => "Virtual device",
+        7 => "Static library",
+        _ => "Unknown",
+    }
+}
+
+/// A node of the version resource, which all share the same header.
+struct Block<'a> {
+    key: String,
+    value: &'a [u8],
+    is_text: bool,
+    children: &'a [u8],
+}
+
+fn align(offset: usize) -> usize {
+    (offset + 3) & !3
+}
+
+/// Reads the block at the start of `data`, returning it and its aligned length.
+fn block(data: &[u8]) -> Option<(Block<'_>, usize)> {
+    let length = read_u16(data, 0)? as usize;
+    let value_length = read_u16(data, 2)? as usize;
+    let is_text = read_u16(data, 4)? == 1;
+    let data = data.get(..length)?;
+
+    let mut key = Vec::new();
+    let mut offset = 6;
+    loop {
+        let character = read_u16(data, offset)?;
+        offset += 2;
+        if character == 0 {
+            break;
+        }
+        key.push(character);
+    }
+    let key = String::from_utf16_lossy(&key);
+
+    // Text values are measured in characters, although some compilers write the size in bytes.
+    let value_start = align(offset).min(length);
+    let value_size = if is_text {
+        value_length * 2
+    } else {
+        value_length
+    };
+    let value_end = (value_start + value_size).min(length);
+    let value = &data[value_start..value_end];
+    let children = &data[align(value_end).min(length)..];
+
+    Some((
+        Block {
+            key,
+            value,
+            is_text,
+            children,
+        },
+        align(length),
+    ))
+}
+
+fn children(data: &[u8]) -> Vec<Block<'_>> {
+    let mut blocks = Vec::new();
+
+    let mut rest = data;
+    while let Some((block, length)) = block(rest) {
+        if length == 0 {
+            break;
+        }
+        blocks.push(block);
+        rest = rest.get(length..).unwrap_or_default();
+    }
+
+    blocks
+}
+
+fn utf16_string(data: &[u8]) -> String {
+    let characters = data
+        .chunks_exact(2)
+        .map(|a| u16::from_le_bytes([a[0], a[1]]))
+        .take_while(|a| *a != 0)
+        .collect::<Vec<_>>();
+
+    String::from_utf16_lossy(&characters).trim().to_string()
+}
+
+fn version_string(most_significant: u32, least_significant: u32) -> String {
+    format!(
+        "{}.{}.{}.{}",
+        most_significant >> 16,
+        most_significant & 0xFFFF,
+        least_significant >> 16,
+        least_significant & 0xFFFF
+    )
+}
+
+fn parse_version_info(data: &[u8]) -> Option<VersionInfo> {
+    let (root, _) = block(data)?;
+    if root.key != "VS_VERSION_INFO" || root.value.len() < VS_FIXEDFILEINFO_SIZE {
+        return None;
+    }
+
+    let fixed = root.value;
+    if read_u32(fixed, 0)? != VS_FFI_SIGNATURE {
+        return None;
+    }
+
+    let file_version = version_string(read_u32(fixed, 8)?, read_u32(fixed, 12)?);
+    let product_version = version_string(read_u32(fixed, 16)?, read_u32(fixed, 20)?);
+    let flags = read_u32(fixed, 24)? & read_u32(fixed, 28)?;
+    let flags = FILE_FLAGS
+        .iter()
+        .filter(|(a, _)| flags & a != 0)
+        .map(|(_, a)| *a)
+        .collect();
+    let file_os = file_os_name(read_u32(fixed, 32)?);
+    let file_type = file_type_name(read_u32(fixed, 36)?);
+
+    let mut strings = Vec::new();
+    for string_file_info in children(root.children)
+        .into_iter()
+        .filter(|a| a.key == "StringFileInfo")
+    {
+        // Only the first language is reported, the others usually repeat it.
+        let Some(table) = children(string_file_info.children).into_iter().next() else {
+            continue;
+        };
+        for string in children(table.children) {
+            let Some((_, name)) = REPORTED_STRINGS
+                .iter()
+                .find(|(a, _)| *a == string.key && string.is_text)
+            else {
+                continue;
+            };
+            let value = utf16_string(string.value);
+            if !value.is_empty() {
+                strings.push((*name, value));
+            }
+        }
+    }
+    strings.sort_by_key(|(name, _)| REPORTED_STRINGS.iter().position(|(_, a)| a == name));
+
+    Some(VersionInfo {
+        file_version,
+        product_version,
+        flags,
+        file_os,
+        file_type,
+        strings,
+    })
+}
+
+/// Newest plausible year mentioned in a copyright string, such as the 2008 in `(C) 2001-2008`.
+fn copyright_year(copyright: &str) -> Option<i32> {
+    copyright
+        .split(|a: char| !a.is_ascii_digit())
+        .filter(|a| a.len() == 4)
+        .filter_map(|a| a.parse::<i32>().ok())
+        .filter(|a| (1980..=2100).contains(a))
+        .max()
+}
+
+fn imports_debug_runtime(pe: &PtrPE) -> bool {
+    let Ok(import_directory) = ImportDirectory::parse(pe) else {
+        return false;
+    };
+
+    import_directory
+        .descriptors
+        .iter()
+        .filter_map(|a| a.get_name(pe).ok().and_then(|a| a.as_str().ok()))
+        .any(|a| DEBUG_RUNTIMES.contains(&a.to_lowercase().as_str()))
+}
+
+pub fn versions_from_version_info(pe: &PtrPE, info: &mut Info, args: &Args) {
+    let Some(version_info) = resources_of_type(pe, ResourceID::Version)
+        .first()
+        .and_then(|a| parse_version_info(a))
+    else {
+        return;
+    };
+
+    print_self_reported(
+        format!(
+            "Version resource has file version {} and product version {}, file type {} and file OS {}",
+            version_info.file_version,
+            version_info.product_version,
+            version_info.file_type,
+            version_info.file_os
+        ),
+        args.quiet,
+    );
+    if !version_info.flags.is_empty() {
+        print_self_reported(
+            format!(
+                "Version resource has file flags {}",
+                version_info.flags.join(", ")
+            ),
+            args.quiet,
+        );
+    }
+    for (name, value) in &version_info.strings {
+        print_self_reported(format!("Version resource has {name} '{value}'"), args.quiet);
+    }
+
+    let debug_runtime = imports_debug_runtime(pe);
+    match (version_info.is_debug(), debug_runtime) {
+        (true, true) => print_heuristic(
+            "Version resource has the DEBUG flag and a debug runtime is imported, which means this is a debug build",
+            args.quiet,
+        ),
+        (true, false) => print_heuristic(
+            "Version resource has the DEBUG flag, which suggests a debug build with a static runtime, unless the flag was left in the resource script",
+            args.quiet,
+        ),
+        (false, true) => print_heuristic(
+            "Debug runtime is imported even though the version resource does not have the DEBUG flag, which means this is a debug build",
+            args.quiet,
+        ),
+        (false, false) => {}
+    }
+    if version_info.is_debug() || debug_runtime {
+        info.set_debug_build();
+    }
+
+    let copyright = version_info
+        .strings
+        .iter()
+        .find(|(name, _)| *name == "Copyright")
+        .and_then(|(_, value)| copyright_year(value));
+    if let Some(year) = copyright {
+        match info.timestamp() {
+            // Copyrights are sometimes given for the coming year near its end.
+            Some(timestamp) if year > timestamp.year() + 1 => {
+                info.set_likely_incorrect(LikelyIncorrect::CopyrightAfterTimestamp);
+                print_heuristic(
+                    format!(
+                        "Copyright year {year} is after the timestamp ({}), which means the timestamp is likely not a real date",
+                        timestamp.year()
+                    ),
+                    args.quiet,
+                );
+            }
+            Some(_) => {}
+            None => {
+                print_heuristic(
+                    format!("Copyright year {year} suggests when the image was built"),
+                    args.quiet,
+                );
+                info.set_era(year);
+            }
+        }
+    }
+
+    info.set_version_info(version_info);
+}
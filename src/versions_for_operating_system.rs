@@ -1,46 +1,101 @@
+use crate::driver::IMAGE_SUBSYSTEM_NATIVE;
 use crate::info::Info;
+use crate::likely_incorrect::LikelyIncorrect;
 use crate::optional_header::OptionalHeader;
+use crate::uefi::is_efi;
+use crate::windows_ce::{is_windows_ce, versions_for_windows_ce};
 use crate::{print_self_reported, Args};
 use std::fmt::Formatter;
 
 #[derive(Debug, Ord, Copy, Clone, PartialOrd, Eq, PartialEq, Hash)]
 pub enum OperatingSystem {
+    MsDos,
+    Windows3_0,
+    Windows3_1,
+    WindowsNt3_1,
+    WindowsNt3_5,
+    WindowsNt3_51,
     Windows95,
+    WindowsNt4_0,
     Windows98,
     Windows2000,
     WindowsMe,
     WindowsXP,
+    WindowsServer2003,
     WindowsVista,
     Windows7,
     Windows8,
     Windows8_1,
     Windows10,
+    WindowsCe1_0,
+    WindowsCe2_0,
+    WindowsCe2_11,
+    WindowsCe3_0,
+    WindowsCe4_0,
+    WindowsCe4_1,
+    WindowsCe4_2,
+    WindowsCe5_0,
+    WindowsCe5_2,
+    WindowsCe6_0,
+    WindowsCe7_0,
+}
+
+impl OperatingSystem {
+    /// Windows CE versions sort after the desktop ones, so they cannot be compared with them.
+    pub fn is_windows_ce(&self) -> bool {
+        *self >= OperatingSystem::WindowsCe1_0
+    }
 }
 
 impl std::fmt::Display for OperatingSystem {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.write_str(match self {
+            OperatingSystem::MsDos => "MS-DOS",
+            OperatingSystem::Windows3_0 => "Windows 3.0",
+            OperatingSystem::Windows3_1 => "Windows 3.1",
+            OperatingSystem::WindowsNt3_1 => "Windows NT 3.1",
+            OperatingSystem::WindowsNt3_5 => "Windows NT 3.5",
+            OperatingSystem::WindowsNt3_51 => "Windows NT 3.51",
             OperatingSystem::Windows95 => "Windows 95",
+            OperatingSystem::WindowsNt4_0 => "Windows NT 4.0",
             OperatingSystem::Windows98 => "Windows 98",
             OperatingSystem::Windows2000 => "Windows 2000",
             OperatingSystem::WindowsMe => "Windows Me",
             OperatingSystem::WindowsXP => "Windows XP",
+            OperatingSystem::WindowsServer2003 => "Windows Server 2003 or Windows XP x64",
             OperatingSystem::WindowsVista => "Windows Vista",
             OperatingSystem::Windows7 => "Windows 7",
             OperatingSystem::Windows8 => "Windows 8",
             OperatingSystem::Windows8_1 => "Windows 8.1",
             OperatingSystem::Windows10 => "Windows 10",
+            OperatingSystem::WindowsCe1_0 => "Windows CE 1.0",
+            OperatingSystem::WindowsCe2_0 => "Windows CE 2.0",
+            OperatingSystem::WindowsCe2_11 => "Windows CE 2.11",
+            OperatingSystem::WindowsCe3_0 => "Windows CE 3.0 (Pocket PC 2000/2002)",
+            OperatingSystem::WindowsCe4_0 => "Windows CE .NET 4.0",
+            OperatingSystem::WindowsCe4_1 => "Windows CE .NET 4.1",
+            OperatingSystem::WindowsCe4_2 => "Windows CE .NET 4.2 (Windows Mobile 2003)",
+            OperatingSystem::WindowsCe5_0 => "Windows CE 5.0 (Windows Mobile 5.0)",
+            OperatingSystem::WindowsCe5_2 => "Windows CE 5.2 (Windows Mobile 6 to 6.5)",
+            OperatingSystem::WindowsCe6_0 => "Windows CE 6.0",
+            OperatingSystem::WindowsCe7_0 => "Windows Embedded Compact 7",
         })
     }
 }
 
-fn versions_to_os(major: u16, minor: u16) -> Option<OperatingSystem> {
+fn versions_to_os(major: u16, minor: u16, subsystem: u16) -> Option<OperatingSystem> {
     Some(match (major, minor) {
+        (3, 10) => OperatingSystem::WindowsNt3_1,
+        (3, 50) => OperatingSystem::WindowsNt3_5,
+        (3, 51) => OperatingSystem::WindowsNt3_51,
+        // Windows 95 does not load native images, so these can only be meant for Windows NT.
+        (4, 0) if subsystem == IMAGE_SUBSYSTEM_NATIVE => OperatingSystem::WindowsNt4_0,
         (4, 0) => OperatingSystem::Windows95,
         (4, 10) => OperatingSystem::Windows98,
         (4, 90) => OperatingSystem::WindowsMe,
         (5, 0) => OperatingSystem::Windows2000,
         (5, 1) => OperatingSystem::WindowsXP,
+        (5, 2) => OperatingSystem::WindowsServer2003,
         (6, 0) => OperatingSystem::WindowsVista,
         (6, 1) => OperatingSystem::Windows7,
         (6, 2) => OperatingSystem::Windows8,
@@ -53,9 +108,18 @@ fn versions_to_os(major: u16, minor: u16) -> Option<OperatingSystem> {
 }
 
 pub fn versions_for_operating_system(pe: &OptionalHeader, info: &mut Info, args: &Args) {
+    if is_efi(pe) {
+        return;
+    }
+
+    if is_windows_ce(pe) {
+        versions_for_windows_ce(pe, info, args);
+        return;
+    }
+
     let major = pe.major_operating_system_version;
     let minor = pe.minor_operating_system_version;
-    let os = versions_to_os(major, minor);
+    let os = versions_to_os(major, minor, pe.subsystem);
     match os {
         None => {
             print_self_reported(
@@ -74,7 +138,7 @@ pub fn versions_for_operating_system(pe: &OptionalHeader, info: &mut Info, args:
 
     let major = pe.major_subsystem_version;
     let minor = pe.minor_subsystem_version;
-    let os = versions_to_os(major, minor);
+    let os = versions_to_os(major, minor, pe.subsystem);
     match os {
         None => {
             print_self_reported(
@@ -85,10 +149,14 @@ pub fn versions_for_operating_system(pe: &OptionalHeader, info: &mut Info, args:
         Some(os) => {
             info.set_subsystem(os);
             if let Some(info_os) = info.os() {
+                let machine_minimum = info.machine().and_then(|a| a.minimum_operating_system());
                 if os == info_os {
                     print_self_reported(format!("Minimum subsystem version {major}.{minor} agrees with operating system version of {os}"), args.quiet)
+                } else if os > info_os && machine_minimum == Some(os) {
+                    // GNU ld raises only the subsystem version to the first release for the machine.
+                    print_self_reported(format!("Minimum subsystem version {major}.{minor} ({os}) is the first version for the machine type, while the operating system version suggests {info_os}"), args.quiet)
                 } else {
-                    info.set_likely_incorrect();
+                    info.set_likely_incorrect(LikelyIncorrect::OperatingSystemMismatch);
                     print_self_reported(format!("Minimum subsystem version {major}.{minor} ({os}) suggests different version than operating system version ({info_os})"), args.quiet)
                 }
             } else {
@@ -0,0 +1,225 @@
+use crate::info::Info;
+use crate::likely_incorrect::LikelyIncorrect;
+use crate::read::{read_rva, read_rva_cstring, read_u16, read_u32};
+use crate::toolchain::Toolchain;
+use crate::{print_heuristic, print_self_reported, Args};
+use exe::{CCharString, ImportDirectory, PtrPE, PE};
+use std::collections::BTreeSet;
+
+const VB_HEADER_MAGIC: &[u8] = b"VB5!";
+const VB_HEADER_SIZE: u32 = 0x68;
+const PROJECT_DATA_SIZE: u32 = 0x24;
+const EXTERNAL_COMPONENT_SIZE: u32 = 8;
+/// Larger component descriptions are not real, they are around a hundred bytes.
+const MAX_COMPONENT_SIZE: u32 = 0x1000;
+
+const PUSH_IMM32: u8 = 0x68;
+const CALL_REL32: u8 = 0xE8;
+
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct VisualBasic {
+    pub project_name: Option<String>,
+    pub p_code: bool,
+    pub forms: u16,
+}
+
+fn runtime_dll(pe: &PtrPE) -> Option<Toolchain> {
+    let import_directory = ImportDirectory::parse(pe).ok()?;
+
+    for descriptor in import_directory.descriptors {
+        let Ok(name) = descriptor.get_name(pe) else {
+            continue;
+        };
+        let Ok(name) = name.as_str() else {
+            continue;
+        };
+
+        match name.to_lowercase().as_str() {
+            "msvbvm60.dll" => return Some(Toolchain::VisualBasic6_0),
+            "msvbvm50.dll" => return Some(Toolchain::VisualBasic5_0),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// File names ending in `.ocx` in the description of an external component.
+fn control_names(data: &[u8]) -> BTreeSet<String> {
+    let mut controls = BTreeSet::new();
+
+    for (i, window) in data.windows(4).enumerate() {
+        if !window.eq_ignore_ascii_case(b".ocx") {
+            continue;
+        }
+
+        let start = data[..i]
+            .iter()
+            .rposition(|a| !(a.is_ascii_alphanumeric() || *a == b'_' || *a == b'-'))
+            .map(|a| a + 1)
+            .unwrap_or(0);
+        if start == i {
+            continue;
+        }
+
+        let name = String::from_utf8_lossy(&data[start..i + 4]).to_uppercase();
+        controls.insert(name);
+    }
+
+    controls
+}
+
+/// Names of ActiveX controls in the external component table of the VB header.
+///
+/// Each entry has a type and the address of a description that starts with its own size, which has the
+/// file name of the control.
+fn referenced_controls(pe: &PtrPE, table: u32, count: u16, image_base: u64) -> BTreeSet<String> {
+    let mut controls = BTreeSet::new();
+    let Some(table) = (table as u64).checked_sub(image_base) else {
+        return controls;
+    };
+    let Some(entries) = read_rva(pe, table as u32, count as u32 * EXTERNAL_COMPONENT_SIZE) else {
+        return controls;
+    };
+
+    for entry in entries.chunks_exact(EXTERNAL_COMPONENT_SIZE as usize) {
+        let Some(address) = read_u32(entry, 4)
+            .and_then(|a| (a as u64).checked_sub(image_base))
+            .and_then(|a| u32::try_from(a).ok())
+        else {
+            continue;
+        };
+        let Some(size) = read_rva(pe, address, 4)
+            .and_then(|a| read_u32(a, 0))
+            .filter(|a| *a <= MAX_COMPONENT_SIZE)
+        else {
+            continue;
+        };
+
+        if let Some(description) = read_rva(pe, address, size) {
+            controls.extend(control_names(description));
+        }
+    }
+
+    controls
+}
+
+pub fn versions_from_visual_basic_header(pe: &PtrPE, info: &mut Info, args: &Args) {
+    let (Ok(entry_point), Ok(image_base)) = (pe.get_entrypoint(), pe.get_image_base()) else {
+        return;
+    };
+
+    // VB5 and VB6 programs always start with `push offset VBHeader; call ThunRTMain`.
+    let Some(entry) = read_rva(pe, entry_point.0, 10) else {
+        return;
+    };
+    if entry[0] != PUSH_IMM32 || entry[5] != CALL_REL32 {
+        return;
+    }
+    let Some(header_va) = read_u32(entry, 1) else {
+        return;
+    };
+    let Some(header_rva) = (header_va as u64).checked_sub(image_base) else {
+        return;
+    };
+    let Some(header) = read_rva(pe, header_rva as u32, VB_HEADER_SIZE) else {
+        return;
+    };
+    if &header[..4] != VB_HEADER_MAGIC {
+        return;
+    }
+
+    let runtime_build = read_u16(header, 0x04).unwrap_or(0);
+    let runtime_revision = read_u16(header, 0x22).unwrap_or(0);
+    let forms = read_u16(header, 0x44).unwrap_or(0);
+    let externals = read_u16(header, 0x46).unwrap_or(0);
+    let external_table = read_u32(header, 0x50).unwrap_or(0);
+
+    let toolchain = runtime_dll(pe);
+    match &toolchain {
+        None => print_self_reported(
+            format!("Entry point pushes a Visual Basic header with runtime build {runtime_build}.{runtime_revision}, but does not link against a Visual Basic runtime"),
+            args.quiet,
+        ),
+        Some(toolchain) => print_self_reported(
+            format!("Entry point pushes a Visual Basic header with runtime build {runtime_build}.{runtime_revision}, which together with the runtime DLL suggests compiler is {toolchain}"),
+            args.quiet,
+        ),
+    }
+
+    // Project strings are stored as offsets from the start of the header.
+    let project_string = |offset: usize| -> Option<String> {
+        let offset = read_u32(header, offset)?;
+        if offset == 0 {
+            return None;
+        }
+
+        read_rva_cstring(pe, (header_rva as u32).checked_add(offset)?).filter(|a| !a.is_empty())
+    };
+
+    let project_name = project_string(0x64);
+    if let Some(project_name) = &project_name {
+        print_self_reported(format!("Project name is '{project_name}'"), args.quiet);
+    }
+    if let Some(exe_name) = project_string(0x5C) {
+        print_self_reported(
+            format!("Project executable name is '{exe_name}'"),
+            args.quiet,
+        );
+    }
+    if let Some(description) = project_string(0x58) {
+        print_self_reported(
+            format!("Project description is '{description}'"),
+            args.quiet,
+        );
+    }
+
+    let p_code = read_u32(header, 0x30)
+        .and_then(|a| (a as u64).checked_sub(image_base))
+        .and_then(|a| read_rva(pe, a as u32, PROJECT_DATA_SIZE))
+        .and_then(|a| read_u32(a, 0x20))
+        .map(|native_code| native_code == 0);
+
+    let p_code = match p_code {
+        None => {
+            print_self_reported("Unable to read Visual Basic project data", args.quiet);
+            info.set_likely_incorrect(LikelyIncorrect::Malformed);
+            false
+        }
+        Some(true) => {
+            print_self_reported(
+                "Project data has no native code pointer, which means it is compiled to P-code",
+                args.quiet,
+            );
+            true
+        }
+        Some(false) => {
+            print_self_reported(
+                "Project data has a native code pointer, which means it is compiled to native code",
+                args.quiet,
+            );
+            false
+        }
+    };
+
+    print_self_reported(
+        format!("Project has {forms} forms and {externals} external components"),
+        args.quiet,
+    );
+
+    for control in referenced_controls(pe, external_table, externals, image_base) {
+        print_heuristic(
+            format!("External component table references the ActiveX control '{control}'"),
+            args.quiet,
+        );
+    }
+
+    if let Some(toolchain) = toolchain {
+        info.add_toolchain(toolchain);
+    }
+    info.set_visual_basic(VisualBasic {
+        project_name,
+        p_code,
+        forms,
+    });
+}
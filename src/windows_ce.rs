@@ -0,0 +1,121 @@
+use crate::compiler_version::CompilerVersion;
+use crate::info::Info;
+use crate::likely_incorrect::LikelyIncorrect;
+use crate::optional_header::OptionalHeader;
+use crate::toolchain::Toolchain;
+use crate::versions_for_operating_system::OperatingSystem;
+use crate::{print_self_reported, Args};
+
+pub const IMAGE_SUBSYSTEM_WINDOWS_CE_GUI: u16 = 9;
+
+pub fn is_windows_ce(header: &OptionalHeader) -> bool {
+    header.subsystem == IMAGE_SUBSYSTEM_WINDOWS_CE_GUI
+}
+
+pub fn versions_to_windows_ce(major: u16, minor: u16) -> Option<OperatingSystem> {
+    Some(match (major, minor) {
+        (1, _) => OperatingSystem::WindowsCe1_0,
+        (2, 0) => OperatingSystem::WindowsCe2_0,
+        (2, 1..=12) => OperatingSystem::WindowsCe2_11,
+        (3, 0) => OperatingSystem::WindowsCe3_0,
+        (4, 0) => OperatingSystem::WindowsCe4_0,
+        (4, 10) => OperatingSystem::WindowsCe4_1,
+        (4, 20) | (4, 21) => OperatingSystem::WindowsCe4_2,
+        (5, 0) | (5, 1) => OperatingSystem::WindowsCe5_0,
+        (5, 2) => OperatingSystem::WindowsCe5_2,
+        (6, 0) => OperatingSystem::WindowsCe6_0,
+        (7, 0) => OperatingSystem::WindowsCe7_0,
+        _ => {
+            return None;
+        }
+    })
+}
+
+/// Linkers shipped with the toolchains for Windows CE, which are separate from the desktop releases.
+pub fn versions_from_windows_ce_linker(major: u8, minor: u8, info: &mut Info, args: &Args) {
+    let (toolchain, linker) = match major {
+        6 => (Toolchain::EmbeddedVisualCpp3_0, None),
+        7 => (Toolchain::EmbeddedVisualCpp4_0, None),
+        8 => (
+            Toolchain::VisualStudio2005SmartDevice,
+            Some(CompilerVersion::VisualStudio2005),
+        ),
+        9 => (
+            Toolchain::VisualStudio2008SmartDevice,
+            Some(CompilerVersion::VisualStudio2008),
+        ),
+        _ => {
+            print_self_reported(
+                format!(
+                    "Linker version is unknown for Windows CE, possibly invalid: {major}.{minor}"
+                ),
+                args.quiet,
+            );
+            return;
+        }
+    };
+
+    print_self_reported(
+        format!(
+            "Linker version is {major}.{minor} for Windows CE, which suggests toolchain is {toolchain}"
+        ),
+        args.quiet,
+    );
+
+    if let Some(linker) = linker {
+        info.set_linker_version(linker);
+    }
+    info.add_toolchain(toolchain);
+}
+
+pub fn versions_for_windows_ce(header: &OptionalHeader, info: &mut Info, args: &Args) {
+    print_self_reported(
+        "Subsystem is WINDOWS_CE_GUI, which means this is a Windows CE executable",
+        args.quiet,
+    );
+
+    let major = header.major_operating_system_version;
+    let minor = header.minor_operating_system_version;
+    match versions_to_windows_ce(major, minor) {
+        None => print_self_reported(
+            format!("Minimum invalid Windows CE version {major}.{minor}."),
+            args.quiet,
+        ),
+        Some(os) => {
+            info.set_os(os);
+            print_self_reported(
+                format!("Minimum operating system version is {major}.{minor} which suggests {os}"),
+                args.quiet,
+            );
+        }
+    }
+
+    let major = header.major_subsystem_version;
+    let minor = header.minor_subsystem_version;
+    match versions_to_windows_ce(major, minor) {
+        None => print_self_reported(
+            format!("Invalid minimum Windows CE subsystem version {major}.{minor}."),
+            args.quiet,
+        ),
+        Some(os) => {
+            info.set_subsystem(os);
+            match info.os() {
+                Some(info_os) if info_os == os => print_self_reported(
+                    format!("Minimum subsystem version {major}.{minor} agrees with operating system version of {os}"),
+                    args.quiet,
+                ),
+                Some(info_os) => {
+                    info.set_likely_incorrect(LikelyIncorrect::OperatingSystemMismatch);
+                    print_self_reported(
+                        format!("Minimum subsystem version {major}.{minor} ({os}) suggests different version than operating system version ({info_os})"),
+                        args.quiet,
+                    );
+                }
+                None => print_self_reported(
+                    format!("Minimum subsystem version is {major}.{minor} which suggests {os}"),
+                    args.quiet,
+                ),
+            }
+        }
+    }
+}